@@ -148,9 +148,11 @@ fn test_contract_with_params(
 
     // Generate and serialize the proof
     let parser = init_parser_with_witnesses(witness);
-    let compiled_circuit = parser.parse(&equation).compile().unwrap();
-    let srs = Srs::deserialize_uncompressed_unchecked(&fs::read(srs_out_path).unwrap()[..]).unwrap();
-    let proof = prover::generate_proof::<Sha256>(&compiled_circuit, srs);
+    let circuit = parser.parse(&equation);
+    let compiled_circuit = circuit.compile().unwrap();
+    let srs: Srs = Srs::from_bytes(&fs::read(srs_out_path).unwrap()).unwrap();
+    let proof =
+        prover::generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs).unwrap();
 
     let mut proof_bytes = Vec::new();
     proof.serialize_uncompressed(&mut proof_bytes).unwrap();
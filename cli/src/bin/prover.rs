@@ -1,13 +1,11 @@
 use std::collections::HashMap;
 use std::error::Error as StdErr;
-use std::fs;
-use std::io::BufReader;
 use std::str::FromStr;
 use std::thread::sleep;
 use std::time::Duration;
 
 use ark_bls12_381::Fr;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::CanonicalSerialize;
 use ckb_hash::blake2b_256;
 use ckb_jsonrpc_types as json_types;
 use ckb_sdk::{
@@ -36,8 +34,11 @@ use glob::glob;
 use serde::Deserialize;
 use sha2::Sha256;
 
-use kzg::srs::Srs;
+use kzg::mmap::MappedSrs;
+use kzg::scheme::KzgScheme;
+use plonk::hash::{CkbBlake2b, Poseidon, TranscriptHash};
 use plonk::prover;
+use plonk::prover::OpeningScheme;
 
 pub const TO_SHANNON: u64 = 100000000;
 
@@ -243,7 +244,7 @@ fn build_plonk_verifier_tx(
 ///
 /// # Returns
 ///
-/// A byte array containing the generated PLONK proof
+/// A byte array containing the generated PLONK proof followed by its public inputs
 pub fn generate_plonk(options: &Options) -> Bytes {
     let mut parser = plonk::parser::Parser::default();
     options.witnesses.split(';').for_each(|key_value| {
@@ -251,17 +252,44 @@ pub fn generate_plonk(options: &Options) -> Bytes {
         assert_eq!(key_value.len(), 2);
         parser.add_witness(key_value[0], Fr::from(key_value[1].parse::<i32>().unwrap()));
     });
+    options
+        .public_inputs
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .for_each(|variable| parser.add_public_input(variable));
 
     // Generate the proof
-    let compiled_circuit = parser.parse(&options.equation).compile().unwrap();
-
-    let f = fs::File::open(&options.srs_path).expect("No file found");
-    let mut reader = BufReader::new(f);
-    let srs = Srs::deserialize_uncompressed_unchecked(&mut reader).expect("Should work!");
-
-    let proof = prover::generate_proof::<Sha256>(&compiled_circuit, srs);
+    let circuit = parser.parse(&options.equation);
+    let compiled_circuit = circuit.compile().unwrap();
+
+    // Map the SRS instead of loading it, so that large SRS files fit in memory
+    let srs: MappedSrs = MappedSrs::open(&options.srs_path).expect("Invalid SRS file");
+    srs.validate().expect("Invalid SRS file");
+    let scheme = KzgScheme::new_mapped(srs).expect("Invalid SRS file");
+
+    // The transcript hash must be the one the verifier contract was generated with
+    let hash = TranscriptHash::from_str(&options.hash).unwrap_or_else(|e| panic!("{}", e));
+    let generate_proof = match hash {
+        TranscriptHash::Sha256 => prover::generate_proof_with_scheme::<Sha256, _>,
+        TranscriptHash::Blake2b => prover::generate_proof_with_scheme::<CkbBlake2b, _>,
+        TranscriptHash::Poseidon => prover::generate_proof_with_scheme::<Poseidon, _>,
+    };
+    let proof = generate_proof(
+        &compiled_circuit,
+        circuit.witness(),
+        &circuit.public_inputs(),
+        scheme,
+        OpeningScheme::Kzg,
+    )
+    .unwrap_or_else(|e| panic!("Failed to generate proof: {}", e));
+    // The verifier contract reads the public inputs after the proof
     let mut proof_bytes = Vec::new();
     proof.serialize_uncompressed(&mut proof_bytes).unwrap();
+    circuit
+        .public_inputs()
+        .serialize_uncompressed(&mut proof_bytes)
+        .unwrap();
 
     proof_bytes.into()
 }
@@ -277,7 +305,13 @@ pub struct Options {
     pub tx_hash: String,
     pub equation: String,
     pub witnesses: String,
+    /// The variables of the equation given to the verifier, separated by ';'
+    #[serde(default)]
+    pub public_inputs: String,
     pub srs_path: String,
+    /// The hash function of the transcript: sha256 (default), blake2b or poseidon
+    #[serde(default)]
+    pub hash: String,
 }
 
 /// Returns the default CKB RPC URL
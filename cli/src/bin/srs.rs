@@ -1,15 +1,22 @@
+use std::fs;
 use std::fs::File;
 use std::io::Write;
 
-use ark_serialize::CanonicalSerialize;
-use clap::Parser;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use clap::{Parser, Subcommand, ValueEnum};
 
-use kzg::srs::Srs;
+use kzg::srs::{max_degree, ContributionProof, Srs};
 
 /// This is a tool for generating a Structured Reference String (SRS).
+///
+/// Without a subcommand, an SRS is generated from a single random secret. The
+/// subcommands allow several parties to run a powers-of-tau ceremony instead.
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// The size of the SRS to generate
     #[clap(long, value_name = "size", default_value_t = 100)]
     size: usize,
@@ -17,29 +24,234 @@ struct Args {
     /// The output file path where the SRS will be saved
     #[clap(long, value_name = "output", default_value = "srs.bin")]
     output: String,
+
+    /// Derives the secret from a known seed, for reproducible tests (requires --insecure)
+    #[clap(long, value_name = "seed")]
+    seed: Option<u64>,
+
+    /// Allows generating an SRS whose secret is known, which must never be used in production
+    #[clap(long)]
+    insecure: bool,
+}
+
+/// Steps of a powers-of-tau ceremony, or import of a public one.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Creates the starting SRS of a ceremony, which must then receive contributions
+    Init {
+        /// The size of the SRS to generate
+        #[clap(long, value_name = "size", default_value_t = 100)]
+        size: usize,
+
+        /// The output file path where the SRS will be saved
+        #[clap(long, value_name = "output", default_value = "srs.bin")]
+        output: String,
+    },
+    /// Contributes a fresh random secret to an SRS
+    Contribute {
+        /// The SRS file to contribute to
+        #[clap(long, value_name = "input")]
+        input: String,
+
+        /// The output file path where the new SRS will be saved
+        #[clap(long, value_name = "output")]
+        output: String,
+
+        /// The output file path where the contribution proof will be saved
+        #[clap(long, value_name = "proof")]
+        proof: String,
+    },
+    /// Verifies that an SRS was obtained by a valid contribution to another one
+    Verify {
+        /// The SRS file before the contribution
+        #[clap(long, value_name = "prev")]
+        prev: String,
+
+        /// The SRS file after the contribution
+        #[clap(long, value_name = "next")]
+        next: String,
+
+        /// The contribution proof file
+        #[clap(long, value_name = "proof")]
+        proof: String,
+    },
+    /// Derives a smaller SRS with the same secret from an existing one
+    Trim {
+        /// The SRS file to trim
+        #[clap(long, value_name = "input")]
+        input: String,
+
+        /// The size of the circuits the trimmed SRS must support
+        #[clap(long, value_name = "size", required_unless_present = "verifier")]
+        size: Option<usize>,
+
+        /// Keeps only the points needed by the verifier contract
+        #[clap(long, conflicts_with = "size")]
+        verifier: bool,
+
+        /// The output file path where the trimmed SRS will be saved
+        #[clap(long, value_name = "output")]
+        output: String,
+    },
+    /// Imports the SRS of a public BLS12-381 ceremony
+    Import {
+        /// The format of the ceremony output
+        #[clap(long, value_enum, value_name = "format")]
+        format: CeremonyFormat,
+
+        /// The ceremony output file
+        #[clap(long, value_name = "input")]
+        input: String,
+
+        /// The number of G1 powers of the transcript to import (Ethereum format only)
+        #[clap(long, value_name = "powers", default_value_t = 4096)]
+        powers: usize,
+
+        /// The output file path where the SRS will be saved
+        #[clap(long, value_name = "output", default_value = "srs.bin")]
+        output: String,
+    },
+}
+
+/// Supported formats of public ceremony outputs.
+#[derive(ValueEnum, Clone, Debug)]
+enum CeremonyFormat {
+    /// The `transcript.json` file of the Ethereum KZG ceremony
+    Ethereum,
+    /// A `.ptau` file of a powers of tau ceremony
+    Ptau,
 }
 
 /// Main function for the SRS generator.
 ///
-/// This function parses command-line arguments, generates an SRS of the specified size,
-/// serializes it, and writes it to the specified output file.
+/// This function parses command-line arguments and either generates an SRS of the
+/// specified size or runs one step of a powers-of-tau ceremony.
 fn main() -> Result<(), std::io::Error> {
     // Parse command-line arguments
     let args = Args::parse();
 
-    // Generate an SRS of the specified size
-    let srs = Srs::new(args.size);
+    match args.command {
+        None => {
+            // Generate an SRS of the specified size
+            let srs = match (args.seed, args.insecure) {
+                (None, _) => Srs::new(args.size),
+                (Some(seed), true) => {
+                    eprintln!("WARNING: the SRS secret is derived from a known seed, do not use it in production!");
+                    Srs::insecure_from_seed(seed, args.size)
+                }
+                (Some(_), false) => {
+                    eprintln!("Refusing to generate an SRS from a known seed without --insecure");
+                    std::process::exit(1);
+                }
+            };
+            write_srs(&srs, &args.output)?;
+
+            // Notify the user that the SRS was generated successfully
+            eprintln!("SRS generated successfully! Output path: {}", args.output);
+        }
+        Some(Command::Init { size, output }) => {
+            let srs = Srs::genesis(size);
+            write_srs(&srs, &output)?;
+            eprintln!("Ceremony initialized! Output path: {}", output);
+        }
+        Some(Command::Contribute {
+            input,
+            output,
+            proof,
+        }) => {
+            let mut srs = read_srs(&input);
+            let contribution = srs.contribute(&mut rand::thread_rng());
+            write_srs(&srs, &output)?;
 
-    // Serialize the SRS into a byte vector
-    let mut srs_bytes = Vec::new();
-    srs.serialize_uncompressed(&mut srs_bytes)
-        .expect("Failed to serialize SRS");
+            let mut proof_bytes = Vec::new();
+            contribution
+                .serialize_compressed(&mut proof_bytes)
+                .expect("Failed to serialize contribution proof");
+            File::create(&proof)?.write_all(&proof_bytes)?;
 
-    // Write the serialized SRS to the specified output file
-    let mut file = File::create(&args.output)?;
-    file.write_all(&srs_bytes)?;
+            eprintln!(
+                "Contribution done! Output path: {}, proof path: {}",
+                output, proof
+            );
+        }
+        Some(Command::Verify { prev, next, proof }) => {
+            let prev_srs = read_srs(&prev);
+            let next_srs = read_srs(&next);
+            let contribution = ContributionProof::deserialize_compressed(&fs::read(&proof)?[..])
+                .expect("Failed to deserialize contribution proof");
 
-    // Notify the user that the SRS was generated successfully
-    eprintln!("SRS generated successfully! Output path: {}", args.output);
+            if !Srs::verify_contribution(&prev_srs, &next_srs, &contribution) {
+                eprintln!("Contribution is invalid!");
+                std::process::exit(1);
+            }
+            eprintln!("Contribution is valid!");
+        }
+        Some(Command::Trim {
+            input,
+            size,
+            verifier,
+            output,
+        }) => {
+            let mut srs = read_srs(&input);
+            if verifier {
+                srs = srs.verifier_subset();
+            } else if let Some(size) = size {
+                if let Err(e) = srs.truncate(max_degree(size)) {
+                    eprintln!("Failed to trim SRS: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            write_srs(&srs, &output)?;
+            eprintln!(
+                "SRS trimmed to {} G1 powers! Output path: {}",
+                srs.g1_points().len(),
+                output
+            );
+        }
+        Some(Command::Import {
+            format,
+            input,
+            powers,
+            output,
+        }) => {
+            let srs = match format {
+                CeremonyFormat::Ethereum => {
+                    Srs::from_ethereum_ceremony(&fs::read_to_string(&input)?, powers)
+                }
+                CeremonyFormat::Ptau => Srs::from_ptau(&fs::read(&input)?),
+            };
+            let srs = match srs {
+                Ok(srs) => srs,
+                Err(e) => {
+                    eprintln!("Failed to import SRS: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            write_srs(&srs, &output)?;
+            eprintln!(
+                "SRS imported successfully with {} G1 powers! Output path: {}",
+                srs.g1_points().len(),
+                output
+            );
+        }
+    }
     Ok(())
 }
+
+/// Reads an SRS file in the versioned or the raw format, checking that it is valid.
+fn read_srs(path: &str) -> Srs {
+    let bytes = fs::read(path).expect("Failed to read SRS file");
+    match Srs::from_bytes(&bytes) {
+        Ok(srs) => srs,
+        Err(e) => {
+            eprintln!("Invalid SRS file {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Writes an SRS to the specified file in the versioned format.
+fn write_srs(srs: &Srs, path: &str) -> Result<(), std::io::Error> {
+    let mut file = File::create(path)?;
+    file.write_all(&srs.to_bytes())
+}
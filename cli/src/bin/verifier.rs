@@ -4,7 +4,7 @@ use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::CanonicalSerialize;
 use clap::Parser;
 use include_dir::{Dir, DirEntry, include_dir};
 use toml::Value;
@@ -12,6 +12,7 @@ use toml::Value;
 use kzg::scheme::KzgScheme;
 use kzg::srs::Srs;
 use plonk::common_preprocessed_input::cpi_parser::CPIGenerator;
+use plonk::hash::TranscriptHash;
 
 static ASSETS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/../contract_templates/plonk_verifier");
 
@@ -34,6 +35,14 @@ struct Args {
     /// The SRS (Structured Reference String) binary file path
     #[clap(long, value_name = "srs", default_value = "srs.bin")]
     srs: String,
+
+    /// The variables of the equation given by the transaction, separated by ';'
+    #[clap(long, value_name = "public_inputs", default_value = "")]
+    public_inputs: String,
+
+    /// The hash function of the transcript: sha256, blake2b or poseidon
+    #[clap(long, value_name = "hash", default_value = "sha256")]
+    hash: TranscriptHash,
 }
 
 /// Main function for generating verifier contracts
@@ -52,9 +61,23 @@ fn main() -> Result<(), std::io::Error> {
     );
 
     let src = Path::new(&args.srs);
-    let srs = Srs::deserialize_uncompressed_unchecked(&fs::read(src).unwrap()[..]).unwrap();
+    let srs: Srs = match Srs::from_bytes(&fs::read(src)?) {
+        Ok(srs) => srs,
+        Err(e) => {
+            eprintln!("Invalid SRS file {}: {}", args.srs, e);
+            std::process::exit(1);
+        }
+    };
+    // The contract only needs the verifier subset of the SRS, in the raw format
+    let mut srs_bytes = Vec::new();
+    srs.verifier_subset()
+        .serialize_uncompressed(&mut srs_bytes)
+        .expect("Failed to serialize SRS");
     let scheme = KzgScheme::new(srs);
-    let cpi = CPIGenerator::default()
+    let mut cpi_generator = CPIGenerator::default();
+    public_input_names(&args.public_inputs)
+        .for_each(|variable| cpi_generator.add_public_input(variable));
+    let cpi = cpi_generator
         .compute_common_preprocessed_input(&args.equation, scheme)
         .expect("Failed to compute CPI");
     let mut cpi_bytes = Vec::new();
@@ -73,6 +96,9 @@ fn main() -> Result<(), std::io::Error> {
     // Replace the package name in Cargo.toml
     replace_package_name(output_path, &args.crate_name);
 
+    // Select the transcript hash of the contract
+    select_transcript_hash(output_path, args.hash);
+
     // Fix the permission problem with find_clang script
     let find_clang_path = output_path.join("scripts").join("find_clang");
     let mut permissions = fs::metadata(&find_clang_path)?.permissions();
@@ -84,9 +110,10 @@ fn main() -> Result<(), std::io::Error> {
     let mut file = File::create(&cpi_file_path)?;
     file.write_all(&cpi_bytes)?;
 
-    // Copy the SRS binary to the output path
-    let srs_dest = output_path.join("src/srs.bin");
-    fs::copy(src, &srs_dest)?;
+    // Write the SRS bytes to a file
+    let srs_file_path = output_path.join("src/srs.bin");
+    let mut file = File::create(&srs_file_path)?;
+    file.write_all(&srs_bytes)?;
 
     println!(
         "Verifier contract generated successfully! Output path: {:?}",
@@ -95,6 +122,14 @@ fn main() -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Splits a list of public input variables separated by ';'
+fn public_input_names(public_inputs: &str) -> impl Iterator<Item = &str> {
+    public_inputs
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+}
+
 /// Recursively extract embedded directory contents to the specified output path
 ///
 /// Iterates through the entries of the given directory and writes files to the specified output path.
@@ -166,3 +201,41 @@ fn replace_package_name(output_path: &Path, crate_name: &str) {
 
     println!("Package name updated successfully.");
 }
+
+/// Select the transcript hash of the contract in the Cargo.toml file
+///
+/// The contract uses Sha256 unless the feature of another hash function is enabled by default.
+///
+/// # Arguments
+///
+/// * `output_path` - The path to the output directory containing the Cargo.toml file
+/// * `hash` - The hash function of the transcript
+///
+/// # Panics
+///
+/// Panics if it fails to read, parse, or write the Cargo.toml file
+fn select_transcript_hash(output_path: &Path, hash: TranscriptHash) {
+    let cargo_toml_path = output_path.join("Cargo.toml");
+    let cargo_toml_content =
+        fs::read_to_string(&cargo_toml_path).expect("Failed to read Cargo.toml file");
+    let mut cargo_toml_value: Value = cargo_toml_content
+        .parse()
+        .expect("Failed to parse Cargo.toml content");
+
+    let default_features = match hash {
+        TranscriptHash::Sha256 => vec![],
+        hash => vec![Value::String(hash.name().to_string())],
+    };
+    if let Some(features) = cargo_toml_value.get_mut("features") {
+        if let Some(features) = features.as_table_mut() {
+            features.insert("default".to_string(), Value::Array(default_features));
+        }
+    }
+
+    let new_cargo_toml_content = toml::to_string(&cargo_toml_value)
+        .expect("Failed to convert updated Cargo.toml content to string");
+    fs::write(cargo_toml_path, new_cargo_toml_content)
+        .expect("Failed to write updated content to Cargo.toml file");
+
+    println!("Transcript hash set to {}.", hash);
+}
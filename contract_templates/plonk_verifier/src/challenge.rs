@@ -2,109 +2,112 @@ use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 use ark_bls12_381::Fr;
-use ark_ff::UniformRand;
-use ark_serialize::{CanonicalSerialize, Write};
-use ark_std::rand::rngs::StdRng;
-use ark_std::rand::SeedableRng;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
 use sha2::Digest;
 
-use crate::data_structures::KzgCommitment;
+use crate::data_structures::{CommonPreprocessedInput, G1Point};
 
-/// A struct for generating challenges using a cryptographic hash function.
-#[derive(Clone, Default)]
-pub struct ChallengeGenerator<T: Digest + Default> {
-    data: Option<Vec<u8>>,
-    generated: bool,
+/// Fiat-Shamir transcript of a proof, identical to the one of the `plonk` crate.
+///
+/// Every message is absorbed with a label, and the state is the hash of the previous state
+/// and the labelled message. Challenges are expanded to 128 bits more than the size of
+/// the field and reduced, then absorbed back into the transcript.
+#[derive(Clone)]
+pub struct Transcript<T: Digest> {
+    state: Vec<u8>,
     _phantom_data: PhantomData<T>,
 }
 
-impl<T: Digest + Default> ChallengeGenerator<T> {
-    /// Creates a new `ChallengeGenerator` from a slice of KZG commitments.
-    ///
-    /// # Arguments
-    ///
-    /// * `kzg_commitments` - A slice of KZG commitments used to initialize the generator.
-    ///
-    /// # Returns
-    ///
-    /// A `ChallengeGenerator` initialized with the provided commitments.
-    pub fn from_commitments(kzg_commitments: &[KzgCommitment]) -> Self {
-        let mut challenge_generator = Self::default();
-        for commitment in kzg_commitments {
-            challenge_generator.feed(commitment);
-        }
-        challenge_generator
+impl<T: Digest> Transcript<T> {
+    /// Creates a transcript for the protocol of the given label.
+    pub fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            state: Vec::new(),
+            _phantom_data: PhantomData,
+        };
+        transcript.append_message(b"dom-sep", label);
+        transcript
     }
 
-    /// Feeds a KZG commitment into the generator to update its internal state.
-    ///
-    /// # Arguments
-    ///
-    /// * `kzg_commitment` - A reference to a KZG commitment to be fed into the generator.
-    pub fn feed(&mut self, kzg_commitment: &KzgCommitment) {
-        let mut hasher = T::default();
-        if let Some(data) = self.data.take() {
-            hasher.update(data);
-        }
-        kzg_commitment
-            .inner()
-            .serialize_uncompressed(&mut HashMarshaller(&mut hasher))
-            .expect("Serialization should be infallible!");
-        self.data = Some(hasher.finalize().to_vec());
-        self.generated = false;
+    /// Absorbs a labelled message.
+    pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        let mut hasher = T::new();
+        hasher.update(&self.state);
+        hasher.update((label.len() as u64).to_le_bytes());
+        hasher.update(label);
+        hasher.update((message.len() as u64).to_le_bytes());
+        hasher.update(message);
+        self.state = hasher.finalize().to_vec();
     }
 
-    /// Generates a random number generator (RNG) seeded with the internal state of the generator.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if called without feeding the generator with new data.
-    fn generate_rng_with_seed(&mut self) -> StdRng {
-        if self.generated {
-            panic!("Generator has already been used. Feed it new data before generating again.");
-        }
-        self.generated = true;
-        let seed_bytes = self
-            .data
-            .as_ref()
-            .expect("No data available to generate seed")[0..8]
-            .try_into()
-            .expect("Failed to generate seed from data");
-        let seed = u64::from_le_bytes(seed_bytes);
-        StdRng::seed_from_u64(seed)
+    /// Absorbs a labelled point, in its uncompressed encoding.
+    pub fn append_point(&mut self, label: &'static [u8], point: &G1Point) {
+        self.append_serializable(label, point);
     }
 
-    /// Generates an array of random challenges.
-    ///
-    /// # Type Parameters
-    ///
-    /// * `N` - The number of challenges to generate.
-    ///
-    /// # Returns
-    ///
-    /// An array of `Fr` elements representing the generated challenges.
-    pub fn generate_challenges<const N: usize>(&mut self) -> [Fr; N] {
-        let mut rng = self.generate_rng_with_seed();
-        let mut challenges = [Fr::default(); N];
-        for challenge in &mut challenges {
-            *challenge = Fr::rand(&mut rng);
-        }
-        challenges
+    /// Absorbs a labelled scalar, in its little-endian encoding.
+    pub fn append_scalar(&mut self, label: &'static [u8], scalar: &Fr) {
+        self.append_serializable(label, scalar);
     }
-}
 
-/// A struct for marshalling data into a hash digest.
-struct HashMarshaller<'a, H: Digest>(&'a mut H);
+    /// Derives a labelled challenge.
+    pub fn challenge_scalar(&mut self, label: &'static [u8]) -> Fr {
+        let length = (Fr::MODULUS_BIT_SIZE as usize + 128).div_ceil(8);
+        let mut bytes = Vec::with_capacity(length);
+        let mut counter = 0u8;
+        while bytes.len() < length {
+            let mut hasher = T::new();
+            hasher.update(&self.state);
+            hasher.update((label.len() as u64).to_le_bytes());
+            hasher.update(label);
+            hasher.update([counter]);
+            bytes.extend_from_slice(&hasher.finalize());
+            counter += 1;
+        }
+        let challenge = Fr::from_le_bytes_mod_order(&bytes[..length]);
+        self.append_scalar(label, &challenge);
+        challenge
+    }
 
-impl<'a, H: Digest> Write for HashMarshaller<'a, H> {
-    #[inline]
-    fn write(&mut self, buf: &[u8]) -> ark_std::io::Result<usize> {
-        Digest::update(self.0, buf);
-        Ok(buf.len())
+    /// Absorbs the circuit of the common preprocessed input, as the prover absorbs its
+    /// verifying key.
+    pub fn append_preprocessed_input(&mut self, cpi: &CommonPreprocessedInput) {
+        self.append_message(b"n", &(cpi.n as u64).to_le_bytes());
+        self.append_scalar(b"k1", &cpi.k1);
+        self.append_scalar(b"k2", &cpi.k2);
+        for (label, commitment) in [
+            (b"q_l" as &'static [u8], &cpi.com_q_lx),
+            (b"q_r", &cpi.com_q_rx),
+            (b"q_m", &cpi.com_q_mx),
+            (b"q_o", &cpi.com_q_ox),
+            (b"q_c", &cpi.com_q_cx),
+            (b"s_sigma_1", &cpi.com_s_sigma_1),
+            (b"s_sigma_2", &cpi.com_s_sigma_2),
+            (b"s_sigma_3", &cpi.com_s_sigma_3),
+        ] {
+            self.append_point(label, commitment.inner());
+        }
+        let rows = cpi
+            .public_input_rows
+            .iter()
+            .flat_map(|row| (*row as u64).to_le_bytes())
+            .collect::<Vec<_>>();
+        self.append_message(b"public_input_rows", &rows);
+        let mut pi_x = Vec::new();
+        for coefficient in &cpi.pi_x.coeffs {
+            coefficient
+                .serialize_uncompressed(&mut pi_x)
+                .expect("Serializing to a vector should be infallible!");
+        }
+        self.append_message(b"pi_x", &pi_x);
     }
 
-    #[inline]
-    fn flush(&mut self) -> ark_std::io::Result<()> {
-        Ok(())
+    fn append_serializable<S: CanonicalSerialize>(&mut self, label: &'static [u8], value: &S) {
+        let mut message = Vec::with_capacity(value.uncompressed_size());
+        value
+            .serialize_uncompressed(&mut message)
+            .expect("Serializing to a vector should be infallible!");
+        self.append_message(label, &message);
     }
 }
@@ -46,6 +46,7 @@ pub struct CommonPreprocessedInput {
     pub com_s_sigma_2: KzgCommitment,
     pub com_s_sigma_3: KzgCommitment,
     pub pi_x: DensePolynomial<Fr>,
+    pub public_input_rows: Vec<usize>,
 }
 
 /// A struct representing the KZG commitment scheme.
@@ -65,19 +66,9 @@ impl KzgScheme {
         Self(srs)
     }
 
-    /// Commits to a scalar using the SRS.
-    ///
-    /// # Arguments
-    ///
-    /// * `para` - The scalar to commit to.
-    ///
-    /// # Returns
-    ///
-    /// A KZG commitment to the scalar.
-    pub fn commit_para(&self, para: Fr) -> KzgCommitment {
-        let g1_0 = *self.0.g1_points.first().unwrap();
-        let commitment = g1_0.mul(para).into();
-        KzgCommitment(commitment)
+    /// Returns the generator point in G1 from the SRS.
+    pub fn g1(&self) -> G1Point {
+        *self.0.g1_points.first().unwrap()
     }
 
     pub fn g2(&self) -> G2Point {
@@ -107,8 +98,6 @@ pub struct Proof {
     pub bar_s_sigma_1: Fr,
     pub bar_s_sigma_2: Fr,
     pub bar_z_w: Fr,
-    pub u: Fr,
-    pub degree: usize,
 }
 
 /// A struct representing a KZG commitment.
@@ -0,0 +1,107 @@
+//! Transcript hash functions other than Sha256, identical to the ones of the `plonk` crate.
+//!
+//! The hash function is selected with the `blake2b` or `poseidon` feature.
+
+#[cfg(feature = "poseidon")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "poseidon")]
+use ark_bls12_381::Fr;
+#[cfg(feature = "poseidon")]
+use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+#[cfg(feature = "poseidon")]
+use ark_crypto_primitives::sponge::CryptographicSponge;
+#[cfg(feature = "poseidon")]
+use ark_ff::PrimeField;
+#[cfg(feature = "poseidon")]
+use ark_serialize::CanonicalSerialize;
+use sha2::digest::consts::U32;
+use sha2::digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+
+#[cfg(feature = "poseidon")]
+use crate::poseidon_constants::{ARK, MDS};
+
+/// Blake2b with a 32 bytes output and the `ckb-default-hash` personalisation.
+#[cfg(feature = "blake2b")]
+#[derive(Clone)]
+pub struct CkbBlake2b(blake2b_simd::State);
+
+#[cfg(feature = "blake2b")]
+impl Default for CkbBlake2b {
+    fn default() -> Self {
+        Self(
+            blake2b_simd::Params::new()
+                .hash_length(32)
+                .personal(b"ckb-default-hash")
+                .to_state(),
+        )
+    }
+}
+
+#[cfg(feature = "blake2b")]
+impl HashMarker for CkbBlake2b {}
+
+#[cfg(feature = "blake2b")]
+impl Update for CkbBlake2b {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+}
+
+#[cfg(feature = "blake2b")]
+impl OutputSizeUser for CkbBlake2b {
+    type OutputSize = U32;
+}
+
+#[cfg(feature = "blake2b")]
+impl FixedOutput for CkbBlake2b {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(self.0.finalize().as_bytes());
+    }
+}
+
+/// Poseidon over the scalar field of BLS12-381, hashing bytes.
+///
+/// The bytes are packed in field elements of 31 bytes followed by their length, absorbed
+/// in a sponge of rate 2 with the S-box x^5, 8 full rounds and 57 partial rounds. The
+/// round keys and the MDS matrix are the constants of `poseidon_constants`, so they are
+/// not generated at run time.
+#[cfg(feature = "poseidon")]
+#[derive(Clone, Default)]
+pub struct Poseidon(Vec<u8>);
+
+#[cfg(feature = "poseidon")]
+impl HashMarker for Poseidon {}
+
+#[cfg(feature = "poseidon")]
+impl Update for Poseidon {
+    fn update(&mut self, data: &[u8]) {
+        self.0.extend_from_slice(data);
+    }
+}
+
+#[cfg(feature = "poseidon")]
+impl OutputSizeUser for Poseidon {
+    type OutputSize = U32;
+}
+
+#[cfg(feature = "poseidon")]
+impl FixedOutput for Poseidon {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        let mut elements = self
+            .0
+            .chunks(31)
+            .map(Fr::from_le_bytes_mod_order)
+            .collect::<Vec<_>>();
+        elements.push(Fr::from(self.0.len() as u64));
+
+        let ark = ARK.iter().map(|row| row.to_vec()).collect();
+        let mds = MDS.iter().map(|row| row.to_vec()).collect();
+        let config = PoseidonConfig::new(8, 57, 5, mds, ark, 2, 1);
+        let mut sponge = PoseidonSponge::new(&config);
+        sponge.absorb(&elements);
+        let hash = sponge.squeeze_field_elements::<Fr>(1)[0];
+        hash.serialize_uncompressed(&mut out[..])
+            .expect("A scalar should fit in 32 bytes!");
+    }
+}
@@ -13,21 +13,35 @@
 mod challenge;
 mod data_structures;
 mod error;
+#[cfg(any(feature = "blake2b", feature = "poseidon"))]
+mod hash;
+#[cfg(feature = "poseidon")]
+mod poseidon_constants;
 mod verify;
 
 #[cfg(test)]
 extern crate alloc;
 
 use crate::data_structures::{CommonPreprocessedInput, Proof, Srs};
+use alloc::vec::Vec;
+use ark_bls12_381::Fr;
 use ark_serialize::CanonicalDeserialize;
 use ckb_std::ckb_constants::Source;
 use ckb_std::debug;
 #[cfg(not(test))]
 use ckb_std::default_alloc;
 use ckb_std::high_level::load_cell_data;
-use sha2::Sha256;
 
 use crate::error::Error;
+#[cfg(feature = "blake2b")]
+use crate::hash::CkbBlake2b as TranscriptHash;
+#[cfg(feature = "poseidon")]
+use crate::hash::Poseidon as TranscriptHash;
+#[cfg(not(any(feature = "blake2b", feature = "poseidon")))]
+use sha2::Sha256 as TranscriptHash;
+
+#[cfg(all(feature = "blake2b", feature = "poseidon"))]
+compile_error!("Only one transcript hash can be selected");
 
 // Embed the Common Preprocessed Input (CPI) and Structured Reference String (SRS) binaries
 const CPI: &[u8] = include_bytes!("cpi.bin");
@@ -43,9 +57,10 @@ default_alloc!(4 * 1024, 2048 * 1024, 64);
 /// This function is the main entry point of the contract and is invoked by the CKB-VM.
 /// It performs the following tasks:
 /// - Loads proof data from the cell.
-/// - Deserializes the proof data.
+/// - Deserializes the proof data and the public inputs following it, rejecting points outside
+///   G1 and non-canonical scalars.
 /// - Deserializes the Common Preprocessed Input (CPI) and Structured Reference String (SRS).
-/// - Verifies the proof using the deserialized CPI and SRS.
+/// - Verifies the proof of the public inputs using the deserialized CPI and SRS.
 /// - Returns an error code if any step fails.
 ///
 /// # Returns
@@ -61,9 +76,17 @@ fn program_entry() -> i8 {
     };
     debug!("Proof data length: {:?}", proof_data.len());
 
-    // Deserialize the proof data
+    // Deserialize the proof data, checking its points are in G1
     debug!("Deserializing proof data...");
-    let proof = match Proof::deserialize_uncompressed_unchecked(&proof_data[..]) {
+    let mut reader = &proof_data[..];
+    let proof = match Proof::deserialize_uncompressed(&mut reader) {
+        Ok(data) => data,
+        Err(_) => return Error::Encoding as i8,
+    };
+
+    // Deserialize the public inputs following the proof
+    debug!("Deserializing public inputs...");
+    let public_inputs = match Vec::<Fr>::deserialize_uncompressed(&mut reader) {
         Ok(data) => data,
         Err(_) => return Error::Encoding as i8,
     };
@@ -88,9 +111,9 @@ fn program_entry() -> i8 {
         }
     };
 
-    // Verify the proof using the deserialized CPI and SRS
+    // Verify the proof of the public inputs using the deserialized CPI and SRS
     debug!("Verifying proof...");
-    match verify::plonk_verify::<Sha256>(proof, cpi, srs) {
+    match verify::plonk_verify::<TranscriptHash>(proof, &public_inputs, cpi, srs) {
         Ok(_) => 0,
         Err(err) => err as i8,
     }
@@ -1,34 +1,35 @@
-use core::ops::Mul;
-
-use ark_bls12_381::{Bls12_381, Fr};
+use ark_bls12_381::{Bls12_381, Fr, G1Projective};
 use ark_ec::pairing::Pairing;
-use ark_ff::{BigInt, Field};
+use ark_ec::VariableBaseMSM;
+use ark_ff::{BigInt, Field, Zero};
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial};
 use ckb_std::debug;
 use sha2::Digest;
 
-use crate::challenge::ChallengeGenerator;
+use crate::challenge::Transcript;
 use crate::data_structures::{CommonPreprocessedInput, KzgScheme, Proof, Srs};
 use crate::error::Error;
 
-pub fn plonk_verify<T: Digest + Default>(
+pub fn plonk_verify<T: Digest>(
     proof: Proof,
+    public_inputs: &[Fr],
     cpi: CommonPreprocessedInput,
     srs: Srs,
 ) -> Result<(), Error> {
+    // Check there is one public input for each public input gate
+    if public_inputs.len() != cpi.public_input_rows.len() {
+        return Err(Error::Verify);
+    }
+
     // Initialize the KZG scheme with the structured reference string (SRS)
-    let scheme = KzgScheme::new(srs.clone());
+    let scheme = KzgScheme::new(srs);
 
     // Commit to various polynomials
     debug!("verify challenge");
 
-    // Generate and verify challenges
-    let (alpha, beta, gamma, evaluation_challenge, v, u) = verify_challenges::<T>(&proof, &scheme);
-
-    // Check if 'u' challenge matches the proof
-    if u != proof.u {
-        return Err(Error::Verify);
-    }
+    // Generate the challenges
+    let (alpha, beta, gamma, evaluation_challenge, v, u) =
+        verify_challenges::<T>(&proof, public_inputs, &cpi);
 
     // Initialize evaluation domain
     let domain = <GeneralEvaluationDomain<Fr>>::new(cpi.n).unwrap();
@@ -37,7 +38,19 @@ pub fn plonk_verify<T: Digest + Default>(
     // Compute zero polynomial evaluation at the evaluation challenge point
     let z_h_e = evaluation_challenge.pow(BigInt::new([domain.size() as u64])) - Fr::from(1);
     let l_1_e = z_h_e / (Fr::from(cpi.n as u128) * (evaluation_challenge - Fr::from(1)));
-    let p_i_e = cpi.pi_x.evaluate(&evaluation_challenge);
+
+    // Compute the public input polynomial PI(X) = pi_x(X) - sum x_i L_i(X) at the evaluation
+    // challenge, with L_i(X) = ω^i (X^n - 1) / (n (X - ω^i)) for the row i of the input
+    let p_i_e = cpi
+        .public_input_rows
+        .iter()
+        .zip(public_inputs)
+        .fold(cpi.pi_x.evaluate(&evaluation_challenge), |p_i_e, (row, value)| {
+            let w_i = domain.element(*row);
+            p_i_e
+                - *value * w_i * z_h_e
+                    / (Fr::from(cpi.n as u128) * (evaluation_challenge - w_i))
+        });
 
     debug!("Compute r0");
 
@@ -50,90 +63,86 @@ pub fn plonk_verify<T: Digest + Default>(
         * (proof.bar_c + gamma)
         * proof.bar_z_w;
 
-    debug!("Compute [D]");
-
-    let d_line1 = cpi.com_q_mx.mul(proof.bar_a * proof.bar_b)
-        + cpi.com_q_lx.mul(proof.bar_a)
-        + cpi.com_q_rx.mul(proof.bar_b)
-        + cpi.com_q_ox.mul(proof.bar_c)
-        + cpi.com_q_cx;
+    debug!("Compute the sides of the pairing");
+
+    // The check of the openings is
+    //   e([W_ζ] + u [W_ζω], [x]) = e(ζ [W_ζ] + uζω [W_ζω] + [F] - [E], [1])
+    // with [F] - [E] = [D] + r_0 [1] + Σ v^i [p_i] - (Σ v^i p_i(ζ) + u z(ωζ)) [1], where the
+    // p_i are a, b, c, s_sigma_1 and s_sigma_2. The right side is computed with a single
+    // multi-scalar multiplication, folding the scalars of the same points together
+    let zeta_slice = evaluation_challenge.pow(BigInt::new([cpi.n as u64 + 2]));
+    let v2 = v * v;
+    let v3 = v2 * v;
+    let v4 = v3 * v;
+    let v5 = v4 * v;
+    let e = v * proof.bar_a
+        + v2 * proof.bar_b
+        + v3 * proof.bar_c
+        + v4 * proof.bar_s_sigma_1
+        + v5 * proof.bar_s_sigma_2
+        + u * proof.bar_z_w;
 
-    let d_line2 = proof.z_commit.mul(
+    let bases = [
+        cpi.com_q_mx.0,
+        cpi.com_q_lx.0,
+        cpi.com_q_rx.0,
+        cpi.com_q_ox.0,
+        cpi.com_q_cx.0,
+        proof.z_commit.0,
+        cpi.com_s_sigma_3.0,
+        // The quotient polynomial is split in slices of n + 2 coefficients
+        proof.t_lo_commit.0,
+        proof.t_mid_commit.0,
+        proof.t_hi_commit.0,
+        proof.a_commit.0,
+        proof.b_commit.0,
+        proof.c_commit.0,
+        cpi.com_s_sigma_1.0,
+        cpi.com_s_sigma_2.0,
+        scheme.g1(),
+        proof.w_ev_x_commit.0,
+        proof.w_ev_wx_commit.0,
+    ];
+    let scalars = [
+        proof.bar_a * proof.bar_b,
+        proof.bar_a,
+        proof.bar_b,
+        proof.bar_c,
+        Fr::from(1),
         (proof.bar_a + beta * evaluation_challenge + gamma)
             * (proof.bar_b + beta * cpi.k1 * evaluation_challenge + gamma)
             * (proof.bar_c + beta * cpi.k2 * evaluation_challenge + gamma)
             * alpha
             + l_1_e * alpha * alpha
             + u,
-    );
-
-    let d_line3 = cpi.com_s_sigma_3.mul(
-        (proof.bar_a + beta * proof.bar_s_sigma_1 + gamma)
+        -(proof.bar_a + beta * proof.bar_s_sigma_1 + gamma)
             * (proof.bar_b + beta * proof.bar_s_sigma_2 + gamma)
             * alpha
             * beta
             * proof.bar_z_w,
-    );
-
-    let d_line4 = (proof.t_lo_commit
-        + proof
-        .t_mid_commit
-        .mul(evaluation_challenge.pow(BigInt::new([proof.degree as u64 + 1])))
-        + proof
-        .t_hi_commit
-        .mul(evaluation_challenge.pow(BigInt::new([proof.degree as u64 * 2 + 2]))))
-        .mul(z_h_e);
-
-    let d = d_line1 + d_line2 - d_line3 - d_line4;
-
-    debug!("Compute [F]");
-
-    let f = d
-        + proof.a_commit.mul(v)
-        + proof.b_commit.mul(v * v)
-        + proof.c_commit.mul(v * v * v)
-        + cpi.com_s_sigma_1.mul(v * v * v * v)
-        + cpi.com_s_sigma_2.mul(v * v * v * v * v);
-
-    debug!("Compute [E]");
-
-    // Compute polynomial E using r0 and other linear combinations
-    let e = -r_0
-        + v * proof.bar_a
-        + v * v * proof.bar_b
-        + v * v * v * proof.bar_c
-        + v * v * v * v * proof.bar_s_sigma_1
-        + v * v * v * v * v * proof.bar_s_sigma_2
-        + u * proof.bar_z_w;
-    let e = scheme.commit_para(e);
-
-    debug!("Compute left side of pairing");
-
-    // Compute the left side of the pairing equation
-    let pairing_left_side = Bls12_381::pairing(
-        (proof.w_ev_x_commit.clone() + proof.w_ev_wx_commit.clone().mul(u)).0,
-        scheme.g2s(),
-    );
-
-    debug!("Compute right side of pairing");
-
-    // Compute the right side of the pairing equation
-    let pairing_right_side = Bls12_381::pairing(
-        (proof.w_ev_x_commit.clone().mul(evaluation_challenge)
-            + proof
-            .w_ev_wx_commit
-            .clone()
-            .mul(u * evaluation_challenge * w)
-            + f
-            - e)
-            .0,
-        scheme.g2(),
-    );
+        -z_h_e,
+        -z_h_e * zeta_slice,
+        -z_h_e * zeta_slice.square(),
+        v,
+        v2,
+        v3,
+        v4,
+        v5,
+        r_0 - e,
+        evaluation_challenge,
+        u * evaluation_challenge * w,
+    ];
+    let pairing_left_side = proof.w_ev_x_commit.0 + proof.w_ev_wx_commit.0 * u;
+    let pairing_right_side = G1Projective::msm_unchecked(&bases, &scalars);
 
     debug!("Check pairing");
 
-    // Verify if both sides of the pairing equation are equal
-    if pairing_left_side != pairing_right_side {
+    // Both sides are checked with a single multi-pairing, sharing the final exponentiation
+    let pairing = Bls12_381::multi_pairing(
+        [pairing_left_side, -pairing_right_side],
+        [scheme.g2s(), scheme.g2()],
+    );
+    if !pairing.is_zero() {
         return Err(Error::Verify);
     }
 
@@ -142,37 +151,43 @@ pub fn plonk_verify<T: Digest + Default>(
     Ok(())
 }
 
-// Function to generate and verify challenges from the proof
-fn verify_challenges<T: Digest + Default>(
+// Function to generate and verify challenges from the proof, with the transcript of the prover
+fn verify_challenges<T: Digest>(
     proof: &Proof,
-    scheme: &KzgScheme,
+    public_inputs: &[Fr],
+    cpi: &CommonPreprocessedInput,
 ) -> (Fr, Fr, Fr, Fr, Fr, Fr) {
-    let commitments = [
-        proof.a_commit.clone(),
-        proof.b_commit.clone(),
-        proof.c_commit.clone(),
-    ];
-    let mut challenge = ChallengeGenerator::<T>::from_commitments(&commitments);
-    let [beta, gamma] = challenge.generate_challenges();
-    challenge.feed(&proof.z_commit);
-    let [alpha] = challenge.generate_challenges();
-    challenge.feed(&proof.t_lo_commit);
-    challenge.feed(&proof.t_mid_commit);
-    challenge.feed(&proof.t_hi_commit);
-    let [evaluation_challenge] = challenge.generate_challenges();
-
-    challenge.feed(&scheme.commit_para(proof.bar_a));
-    challenge.feed(&scheme.commit_para(proof.bar_b));
-    challenge.feed(&scheme.commit_para(proof.bar_c));
-    challenge.feed(&scheme.commit_para(proof.bar_s_sigma_1));
-    challenge.feed(&scheme.commit_para(proof.bar_s_sigma_2));
-    challenge.feed(&scheme.commit_para(proof.bar_z_w));
-    let [v] = challenge.generate_challenges();
-
-    challenge.feed(&proof.w_ev_x_commit);
-    challenge.feed(&proof.w_ev_wx_commit);
-
-    let [u] = challenge.generate_challenges();
+    let mut transcript = Transcript::<T>::new(b"plonk");
+    transcript.append_preprocessed_input(cpi);
+    for public_input in public_inputs {
+        transcript.append_scalar(b"pi", public_input);
+    }
+
+    transcript.append_point(b"a", proof.a_commit.inner());
+    transcript.append_point(b"b", proof.b_commit.inner());
+    transcript.append_point(b"c", proof.c_commit.inner());
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
+
+    transcript.append_point(b"z", proof.z_commit.inner());
+    let alpha = transcript.challenge_scalar(b"alpha");
+
+    transcript.append_point(b"t_lo", proof.t_lo_commit.inner());
+    transcript.append_point(b"t_mid", proof.t_mid_commit.inner());
+    transcript.append_point(b"t_hi", proof.t_hi_commit.inner());
+    let evaluation_challenge = transcript.challenge_scalar(b"zeta");
+
+    transcript.append_scalar(b"bar_a", &proof.bar_a);
+    transcript.append_scalar(b"bar_b", &proof.bar_b);
+    transcript.append_scalar(b"bar_c", &proof.bar_c);
+    transcript.append_scalar(b"bar_s_sigma_1", &proof.bar_s_sigma_1);
+    transcript.append_scalar(b"bar_s_sigma_2", &proof.bar_s_sigma_2);
+    transcript.append_scalar(b"bar_z_w", &proof.bar_z_w);
+    let v = transcript.challenge_scalar(b"v");
+
+    transcript.append_point(b"w_ev_x", proof.w_ev_x_commit.inner());
+    transcript.append_point(b"w_ev_wx", proof.w_ev_wx_commit.inner());
+    let u = transcript.challenge_scalar(b"u");
 
     (alpha, beta, gamma, evaluation_challenge, v, u)
 }
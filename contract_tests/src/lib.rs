@@ -0,0 +1,84 @@
+//! Host-side tests of the verifier contract.
+//!
+//! The contract only builds for CKB-VM, so its modules that must stay identical to the
+//! `plonk` crate are compiled here instead, with every transcript hash enabled, and
+//! checked against the prover.
+
+#![cfg(test)]
+
+extern crate alloc;
+
+#[allow(dead_code)]
+#[path = "../../contract_templates/plonk_verifier/src/challenge.rs"]
+mod challenge;
+#[allow(dead_code)]
+#[path = "../../contract_templates/plonk_verifier/src/data_structures.rs"]
+mod data_structures;
+#[path = "../../contract_templates/plonk_verifier/src/hash.rs"]
+mod hash;
+#[path = "../../contract_templates/plonk_verifier/src/poseidon_constants.rs"]
+mod poseidon_constants;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_crypto_primitives::sponge::poseidon::find_poseidon_ark_and_mds;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use kzg::srs::Srs;
+use plonk::common_preprocessed_input::cpi_parser::CommonPreprocessedInput;
+use plonk::key::{setup, VerifyingKey};
+use plonk::parser::Parser;
+
+#[test]
+fn poseidon_constants() {
+    let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(Fr::MODULUS_BIT_SIZE as u64, 2, 8, 57, 0);
+    assert_eq!(ark, poseidon_constants::ARK.map(Vec::from).to_vec());
+    assert_eq!(mds, poseidon_constants::MDS.map(Vec::from).to_vec());
+}
+
+/// Checks that both transcripts derive the same challenges from the same messages.
+fn transcript_parity<H, C>(verifying_key: &VerifyingKey)
+where
+    H: sha2_host::Digest + Default,
+    C: sha2::Digest,
+{
+    let mut bytes = Vec::new();
+    CommonPreprocessedInput::from(verifying_key.clone())
+        .serialize_uncompressed(&mut bytes)
+        .unwrap();
+    let cpi =
+        data_structures::CommonPreprocessedInput::deserialize_uncompressed_unchecked(&bytes[..])
+            .unwrap();
+
+    let mut host = plonk::transcript::Transcript::<H>::new(b"plonk");
+    let mut contract = challenge::Transcript::<C>::new(b"plonk");
+    verifying_key.append_to_transcript(&mut host);
+    contract.append_preprocessed_input(&cpi);
+
+    let point = (ark_bls12_381::G1Affine::generator() * Fr::from(5)).into_affine();
+    host.append_scalar(b"pi", &Fr::from(35));
+    contract.append_scalar(b"pi", &Fr::from(35));
+    host.append_point(b"a", &point);
+    contract.append_point(b"a", &point);
+    host.append_message(b"w", b"message");
+    contract.append_message(b"w", b"message");
+    for label in [b"beta" as &'static [u8], b"gamma", b"alpha"] {
+        assert_eq!(
+            host.challenge_scalar::<Fr>(label),
+            contract.challenge_scalar(label)
+        );
+    }
+}
+
+#[test]
+fn transcripts_match_prover() {
+    let mut parser = Parser::<Fr>::default();
+    parser.add_public_input("y");
+    let compiled_circuit = parser.parse_structure("x^3+x+5=y").compile().unwrap();
+    let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+    let (_, verifying_key) = setup(&compiled_circuit, &srs).unwrap();
+
+    transcript_parity::<sha2_host::Sha256, sha2::Sha256>(&verifying_key);
+    transcript_parity::<plonk::hash::CkbBlake2b, hash::CkbBlake2b>(&verifying_key);
+    transcript_parity::<plonk::hash::Poseidon, hash::Poseidon>(&verifying_key);
+}
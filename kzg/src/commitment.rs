@@ -1,14 +1,16 @@
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use crate::types::G1Point;
 
 /// Commitment contains result
 #[derive(Debug, Clone, PartialEq, Eq, CanonicalDeserialize, CanonicalSerialize)]
-pub struct KzgCommitment(pub G1Point);
+pub struct KzgCommitment<E: Pairing = Bls12_381>(pub G1Point<E>);
 
-impl KzgCommitment {
+impl<E: Pairing> KzgCommitment<E> {
     /// A reference to the inner `G1Point` contained within the commitment.
-    pub fn inner(&self) -> &G1Point {
+    pub fn inner(&self) -> &G1Point<E> {
         &self.0
     }
 }
@@ -17,13 +19,19 @@ impl KzgCommitment {
 mod tests {
     use std::ops::Mul;
 
-    use ark_bls12_381::Fr;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::pairing::Pairing;
     use ark_ec::{AffineRepr, CurveGroup};
-    use ark_ff::One;
-    use ark_poly::{DenseUVPolynomial, Polynomial};
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_poly::{
+        DenseUVPolynomial, EvaluationDomain, Evaluations, GeneralEvaluationDomain, Polynomial,
+    };
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
+    use crate::error::KzgError;
+    use crate::opening::KzgOpening;
     use crate::scheme::KzgScheme;
-    use crate::srs::Srs;
+    use crate::srs::{HidingSrs, Srs};
     use crate::types::{G1Point, Poly};
 
     #[test]
@@ -33,22 +41,22 @@ mod tests {
     /// opening the commitment, and verifying the opening.
     fn commit() {
         let secret = Fr::from(2);
-        let srs = Srs::new_from_secret(secret, 10);
+        let srs = Srs::<Bls12_381>::new_from_secret(&mut secret.clone(), 10);
         let scheme = KzgScheme::new(srs);
-        let poly = Poly::from_coefficients_slice(&[1.into(), 2.into(), 3.into()]);
-        let commitment = scheme.commit(&poly);
+        let poly = Poly::<Bls12_381>::from_coefficients_slice(&[1.into(), 2.into(), 3.into()]);
+        let commitment = scheme.commit(&poly).unwrap();
         let d = Fr::one();
 
         assert_eq!(poly.evaluate(&d), 6.into());
 
         assert_eq!(
             commitment.0,
-            G1Point::generator()
+            G1Point::<Bls12_381>::generator()
                 .mul(poly.evaluate(&secret))
                 .into_affine()
         );
-        let opening = scheme.open(poly, d);
-        assert!(scheme.verify(&commitment, &opening, d));
+        let opening = scheme.open(poly, d).unwrap();
+        assert!(scheme.verify(&commitment, &opening, d).is_ok());
     }
 
     #[test]
@@ -57,14 +65,206 @@ mod tests {
     /// This test validates the correctness of scalar multiplying a commitment
     /// by a factor in the KZG scheme.
     fn scalar_mul() {
-        let srs = Srs::new(5);
+        let srs = Srs::<Bls12_381>::new(5);
         let scheme = KzgScheme::new(srs);
-        let coeffs = [1, 2, 3, 4, 5].map(|e| Fr::from(e));
-        let poly = Poly::from_coefficients_slice(&coeffs);
-        let commit1 = scheme.commit(&poly);
+        let coeffs = [1, 2, 3, 4, 5].map(Fr::from);
+        let poly = Poly::<Bls12_381>::from_coefficients_slice(&coeffs);
+        let commit1 = scheme.commit(&poly).unwrap();
         let factor = Fr::from(9);
         let poly2 = poly.mul(factor);
-        let commit2 = scheme.commit(&poly2);
+        let commit2 = scheme.commit(&poly2).unwrap();
         assert_eq!(commit1 * factor, commit2);
     }
+
+    #[test]
+    /// Tests that commitments with fixed-base precomputation match plain commitments.
+    fn precomputed_commit() {
+        let srs = Srs::<Bls12_381>::new(16);
+        let scheme = KzgScheme::new(srs.clone());
+        let precomputed_scheme = KzgScheme::new_with_precomputation(srs, 6).unwrap();
+        let coeffs = (1..=16).map(Fr::from).collect::<Vec<_>>();
+        let poly = Poly::<Bls12_381>::from_coefficients_vec(coeffs);
+
+        let commitment = scheme.commit(&poly).unwrap();
+        assert_eq!(commitment, precomputed_scheme.commit(&poly).unwrap());
+        assert_eq!(
+            scheme.commit_para(Fr::from(7)).unwrap(),
+            precomputed_scheme.commit_para(Fr::from(7)).unwrap()
+        );
+
+        let d = Fr::from(3);
+        let opening = precomputed_scheme.open(poly, d).unwrap();
+        assert!(scheme.verify(&commitment, &opening, d).is_ok());
+    }
+
+    #[test]
+    /// Tests opening several polynomials at one point with a single witness.
+    ///
+    /// This test checks that a batched opening verifies, that it matches the aggregation
+    /// of individual openings, and that a wrong evaluation is rejected.
+    fn batch_open() {
+        let srs = Srs::<Bls12_381>::new(10);
+        let scheme = KzgScheme::new(srs);
+        let polys = [
+            Poly::<Bls12_381>::from_coefficients_slice(&[1.into(), 2.into(), 3.into()]),
+            Poly::<Bls12_381>::from_coefficients_slice(&[4.into(), 5.into()]),
+            Poly::<Bls12_381>::from_coefficients_slice(&[6.into(), 7.into(), 8.into(), 9.into()]),
+        ];
+        let commitments = polys
+            .iter()
+            .map(|p| scheme.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let z = Fr::from(5);
+        let challenge = Fr::from(11);
+        let mut evals = polys.iter().map(|p| p.evaluate(&z)).collect::<Vec<_>>();
+
+        let opening = scheme.batch_open(&polys, z, challenge).unwrap();
+        assert!(scheme
+            .batch_verify(&commitments, &evals, z, challenge, &opening)
+            .is_ok());
+
+        let openings = polys
+            .iter()
+            .map(|p| scheme.open(p.clone(), z).unwrap())
+            .collect::<Vec<_>>();
+        let aggregated = KzgOpening::aggregate(&openings, challenge);
+        assert_eq!(aggregated.0, opening.0);
+        assert_eq!(aggregated.1, opening.1);
+
+        let sum = openings[0].clone() + openings[1].clone();
+        let sum_poly = &polys[0] + &polys[1];
+        let sum_commitment = scheme.commit(&sum_poly).unwrap();
+        assert!(scheme.verify(&sum_commitment, &sum, z).is_ok());
+
+        evals[1] += Fr::one();
+        assert_eq!(
+            scheme.batch_verify(&commitments, &evals, z, challenge, &opening),
+            Err(KzgError::InvalidOpening)
+        );
+    }
+
+    #[test]
+    /// Tests that committing to evaluations matches committing to the interpolated polynomial.
+    fn commit_evaluations() {
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+        let scheme = KzgScheme::new(Srs::<Bls12_381>::new(8))
+            .with_lagrange_basis(domain)
+            .unwrap();
+
+        let evaluations = (1..=8).map(Fr::from).collect::<Vec<_>>();
+        let poly = Evaluations::from_vec_and_domain(evaluations.clone(), domain).interpolate();
+        assert_eq!(
+            scheme.commit_evaluations(&evaluations).unwrap(),
+            scheme.commit(&poly).unwrap()
+        );
+
+        // Missing evaluations are zeros.
+        let evaluations = (1..=5).map(Fr::from).collect::<Vec<_>>();
+        let poly = Evaluations::from_vec_and_domain(evaluations.clone(), domain).interpolate();
+        assert_eq!(
+            scheme.commit_evaluations(&evaluations).unwrap(),
+            scheme.commit(&poly).unwrap()
+        );
+    }
+
+    #[test]
+    /// Tests that an SRS too small for a polynomial and empty polynomials are reported.
+    fn commit_errors() {
+        let scheme = KzgScheme::new(Srs::<Bls12_381>::new(4));
+        let poly = Poly::<Bls12_381>::from_coefficients_vec((1..=10).map(Fr::from).collect());
+        assert_eq!(
+            scheme.commit(&poly),
+            Err(KzgError::DegreeExceedsSrs { degree: 9, max: 6 })
+        );
+        assert!(matches!(
+            scheme.open(poly, Fr::from(3)),
+            Err(KzgError::DegreeExceedsSrs { degree: 8, max: 6 })
+        ));
+        assert!(matches!(
+            scheme.open(Poly::<Bls12_381>::zero(), Fr::from(3)),
+            Err(KzgError::EmptyPolynomial)
+        ));
+    }
+
+    #[test]
+    /// Tests hiding commitments, their openings and their verification.
+    fn commit_hiding() {
+        let rng = &mut rand::thread_rng();
+        let scheme = KzgScheme::new_hiding(HidingSrs::<Bls12_381>::new(8));
+        let poly = Poly::<Bls12_381>::rand(7, rng);
+        let blinding = Poly::<Bls12_381>::rand(7, rng);
+        let commitment = scheme.commit_hiding(&poly, &blinding).unwrap();
+
+        // The same polynomial with another blinding gives another commitment.
+        let other = scheme
+            .commit_hiding(&poly, &Poly::<Bls12_381>::rand(7, rng))
+            .unwrap();
+        assert_ne!(commitment, other);
+        assert_ne!(commitment, scheme.commit(&poly).unwrap());
+
+        let d = Fr::rand(rng);
+        let opening = scheme.open_hiding(poly.clone(), blinding, d).unwrap();
+        assert_eq!(opening.eval(), poly.evaluate(&d));
+        assert!(scheme.verify_hiding(&commitment, &opening, d).is_ok());
+        assert_eq!(
+            scheme.verify_hiding(&commitment, &opening, d + Fr::one()),
+            Err(KzgError::InvalidOpening)
+        );
+
+        let mut tampered = opening;
+        tampered.2 += Fr::one();
+        assert_eq!(
+            scheme.verify_hiding(&commitment, &tampered, d),
+            Err(KzgError::InvalidOpening)
+        );
+
+        // The blinding polynomial is bounded by the blinding points, not by the G1 points.
+        let (srs, h_points) = HidingSrs::<Bls12_381>::new(8).into_parts();
+        let mut bytes = Vec::new();
+        (srs, &h_points[..4])
+            .serialize_uncompressed(&mut bytes)
+            .unwrap();
+        let short = KzgScheme::new_hiding(
+            HidingSrs::<Bls12_381>::deserialize_uncompressed_unchecked(&bytes[..]).unwrap(),
+        );
+        assert_eq!(
+            short.commit_hiding(&poly, &Poly::<Bls12_381>::rand(4, rng)),
+            Err(KzgError::DegreeExceedsSrs { degree: 4, max: 3 })
+        );
+        assert!(short
+            .commit_hiding(&poly, &Poly::<Bls12_381>::rand(3, rng))
+            .is_ok());
+
+        // A plain scheme cannot produce hiding commitments.
+        let plain = KzgScheme::new(Srs::<Bls12_381>::new(8));
+        assert_eq!(
+            plain.commit_hiding(&poly, &poly),
+            Err(KzgError::MissingHidingPoints)
+        );
+    }
+
+    /// Commits, opens and verifies on the given curve.
+    fn commit_on<E: Pairing>() {
+        let rng = &mut rand::thread_rng();
+        let srs = Srs::<E>::new(16);
+        assert!(srs.validate().is_ok());
+        let scheme = KzgScheme::new(srs);
+        let poly = Poly::<E>::rand(15, rng);
+        let commitment = scheme.commit(&poly).unwrap();
+
+        let d = E::ScalarField::rand(rng);
+        let opening = scheme.open(poly, d).unwrap();
+        assert!(scheme.verify(&commitment, &opening, d).is_ok());
+        assert_eq!(
+            scheme.verify(&commitment, &opening, d + E::ScalarField::one()),
+            Err(KzgError::InvalidOpening)
+        );
+    }
+
+    #[test]
+    /// Tests the KZG scheme on curves other than BLS12-381.
+    fn other_curves() {
+        commit_on::<ark_bn254::Bn254>();
+        commit_on::<ark_bls12_377::Bls12_377>();
+    }
 }
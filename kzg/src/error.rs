@@ -0,0 +1,67 @@
+use std::fmt::{Display, Formatter};
+
+/// Errors returned by the KZG scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KzgError {
+    /// The polynomial has a higher degree than the SRS supports.
+    DegreeExceedsSrs {
+        /// The degree of the polynomial.
+        degree: usize,
+        /// The maximum degree supported by the SRS.
+        max: usize,
+    },
+    /// The polynomial to be opened has no coefficients.
+    EmptyPolynomial,
+    /// Evaluations were committed to without a precomputed Lagrange basis.
+    MissingLagrangeBasis,
+    /// A hiding commitment was requested from a scheme without blinding points.
+    MissingHidingPoints,
+    /// The numbers of commitments, points and evaluations do not match.
+    LengthMismatch,
+    /// The opening does not match the commitment and evaluation.
+    InvalidOpening,
+    /// The window size of fixed-base precomputation tables is not in `1..=16`.
+    InvalidWindow(usize),
+    /// The SRS, or the memory-mapped SRS file, is malformed.
+    InvalidSrs(String),
+}
+
+impl Display for KzgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KzgError::DegreeExceedsSrs { degree, max } => write!(
+                f,
+                "KZG: polynomial of degree {} exceeds the maximum degree {} supported by the SRS",
+                degree, max
+            ),
+            KzgError::EmptyPolynomial => write!(f, "KZG: cannot open an empty polynomial"),
+            KzgError::MissingLagrangeBasis => write!(
+                f,
+                "KZG: Lagrange basis must be precomputed with `with_lagrange_basis`"
+            ),
+            KzgError::MissingHidingPoints => write!(
+                f,
+                "KZG: hiding commitments need a scheme created with `new_hiding`"
+            ),
+            KzgError::LengthMismatch => write!(
+                f,
+                "KZG: numbers of commitments, points and evaluations do not match"
+            ),
+            KzgError::InvalidOpening => write!(f, "KZG: invalid opening"),
+            KzgError::InvalidWindow(window) => write!(
+                f,
+                "KZG: window size {} of the precomputation tables must be between 1 and 16",
+                window
+            ),
+            KzgError::InvalidSrs(reason) => write!(f, "KZG: invalid SRS: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for KzgError {}
+
+impl From<KzgError> for String {
+    fn from(error: KzgError) -> Self {
+        error.to_string()
+    }
+}
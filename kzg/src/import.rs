@@ -0,0 +1,377 @@
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr};
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInt, BigInteger, PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalDeserialize;
+use serde::Deserialize;
+
+use crate::msm::msm;
+use crate::opening::powers_of;
+use crate::srs::Srs;
+use crate::types::{G1Point, G2Point};
+
+/// Size in bytes of a BLS12-381 base field element.
+const FQ_BYTES: usize = 48;
+
+/// Output of the Ethereum KZG ceremony, as published in `transcript.json`.
+#[derive(Debug, Deserialize)]
+struct EthereumCeremony {
+    transcripts: Vec<EthereumTranscript>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EthereumTranscript {
+    num_g1_powers: usize,
+    powers_of_tau: EthereumPowers,
+    witness: EthereumWitness,
+}
+
+#[derive(Debug, Deserialize)]
+struct EthereumPowers {
+    #[serde(rename = "G1Powers")]
+    g1_powers: Vec<String>,
+    #[serde(rename = "G2Powers")]
+    g2_powers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EthereumWitness {
+    running_products: Vec<String>,
+    pot_pubkeys: Vec<String>,
+}
+
+impl Srs {
+    /// Imports an SRS from the output of the Ethereum KZG ceremony.
+    ///
+    /// The ceremony publishes one transcript per size. Besides the powers themselves,
+    /// the chain of contributions recorded in the transcript witness is verified.
+    ///
+    /// # Parameters
+    ///
+    /// - `json`: The content of the ceremony `transcript.json` file.
+    /// - `num_g1_powers`: The number of G1 powers of the transcript to import.
+    ///
+    /// # Returns
+    ///
+    /// A new `Srs` instance, or an error if the transcript is malformed or invalid.
+    pub fn from_ethereum_ceremony(json: &str, num_g1_powers: usize) -> Result<Self, String> {
+        let ceremony: EthereumCeremony =
+            serde_json::from_str(json).map_err(|e| format!("Invalid ceremony JSON: {}", e))?;
+        let transcript = ceremony
+            .transcripts
+            .into_iter()
+            .find(|transcript| transcript.num_g1_powers == num_g1_powers)
+            .ok_or(format!("No transcript with {} G1 powers", num_g1_powers))?;
+
+        let g1_points = transcript
+            .powers_of_tau
+            .g1_powers
+            .iter()
+            .map(|point| parse_hex_point::<G1Point>(point))
+            .collect::<Result<Vec<_>, _>>()?;
+        if g1_points.len() != num_g1_powers {
+            return Err("Transcript has a wrong number of G1 powers".to_string());
+        }
+        let g2_points = transcript
+            .powers_of_tau
+            .g2_powers
+            .iter()
+            .take(2)
+            .map(|point| parse_hex_point::<G2Point>(point))
+            .collect::<Result<Vec<_>, _>>()?;
+        if g2_points.len() != 2 {
+            return Err("Transcript needs at least 2 G2 powers".to_string());
+        }
+
+        let running_products = transcript
+            .witness
+            .running_products
+            .iter()
+            .map(|point| parse_hex_point::<G1Point>(point))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pot_pubkeys = transcript
+            .witness
+            .pot_pubkeys
+            .iter()
+            .map(|point| parse_hex_point::<G2Point>(point))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !is_valid_contribution_chain(&running_products, &pot_pubkeys)
+            || running_products.last() != g1_points.get(1)
+        {
+            return Err("Transcript witness does not match its powers".to_string());
+        }
+
+        Srs::from_points(g1_points, g2_points[0], g2_points[1])
+    }
+
+    /// Imports an SRS from a `.ptau` file of a BLS12-381 powers of tau ceremony.
+    ///
+    /// Only the `tauG1` and `tauG2` sections are used.
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The content of the `.ptau` file.
+    ///
+    /// # Returns
+    ///
+    /// A new `Srs` instance, or an error if the file is malformed or invalid.
+    pub fn from_ptau(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = PtauReader { bytes };
+        if reader.take(4)? != b"ptau" {
+            return Err("Not a ptau file".to_string());
+        }
+        let _version = reader.read_u32()?;
+        let num_sections = reader.read_u32()?;
+
+        let mut header = None;
+        let mut tau_g1 = None;
+        let mut tau_g2 = None;
+        for _ in 0..num_sections {
+            let section_type = reader.read_u32()?;
+            let size = reader.read_u64()? as usize;
+            let data = reader.take(size)?;
+            match section_type {
+                1 => header = Some(data),
+                2 => tau_g1 = Some(data),
+                3 => tau_g2 = Some(data),
+                _ => {}
+            }
+        }
+
+        let mut header = PtauReader {
+            bytes: header.ok_or("Missing ptau header section")?,
+        };
+        if header.read_u32()? as usize != FQ_BYTES
+            || header.take(FQ_BYTES)? != Fq::MODULUS.to_bytes_le().as_slice()
+        {
+            return Err("The ptau file is not over BLS12-381".to_string());
+        }
+
+        let g1_points = tau_g1
+            .ok_or("Missing ptau tauG1 section")?
+            .chunks_exact(2 * FQ_BYTES)
+            .map(|chunk| {
+                let x = read_fq(&chunk[..FQ_BYTES])?;
+                let y = read_fq(&chunk[FQ_BYTES..])?;
+                check_point(x, y)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2_points = tau_g2
+            .ok_or("Missing ptau tauG2 section")?
+            .chunks_exact(4 * FQ_BYTES)
+            .take(2)
+            .map(|chunk| {
+                let x = Fq2::new(
+                    read_fq(&chunk[..FQ_BYTES])?,
+                    read_fq(&chunk[FQ_BYTES..2 * FQ_BYTES])?,
+                );
+                let y = Fq2::new(
+                    read_fq(&chunk[2 * FQ_BYTES..3 * FQ_BYTES])?,
+                    read_fq(&chunk[3 * FQ_BYTES..])?,
+                );
+                check_point(x, y)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if g2_points.len() != 2 {
+            return Err("The ptau file needs at least 2 G2 powers".to_string());
+        }
+
+        Srs::from_points(g1_points, g2_points[0], g2_points[1])
+    }
+}
+
+/// Checks that each contribution multiplies the running product by its public key.
+///
+/// The checks `e(P_(i+1), [1]_2) == e(P_i, K_i)` are batched with a random linear
+/// combination into a single multi-pairing.
+fn is_valid_contribution_chain(running_products: &[G1Point], pot_pubkeys: &[G2Point]) -> bool {
+    if running_products.len() != pot_pubkeys.len() + 1
+        || running_products[0] != G1Point::<Bls12_381>::generator()
+        || pot_pubkeys.iter().any(|pubkey| pubkey.is_zero())
+    {
+        return false;
+    }
+
+    let rho = Fr::rand(&mut rand::thread_rng());
+    let powers = powers_of(rho, pot_pubkeys.len());
+    let left = msm(&running_products[1..], &powers).into_affine();
+    let right = running_products
+        .iter()
+        .zip(&powers)
+        .map(|(product, power)| (*product * power).into_affine());
+
+    let g1_terms = std::iter::once(left).chain(right).collect::<Vec<_>>();
+    let g2_terms = std::iter::once(-G2Point::<Bls12_381>::generator())
+        .chain(pot_pubkeys.iter().copied())
+        .collect::<Vec<_>>();
+    Bls12_381::multi_pairing(g1_terms, g2_terms).is_zero()
+}
+
+/// Parses a `0x`-prefixed hex string holding a compressed point.
+fn parse_hex_point<P: CanonicalDeserialize>(point: &str) -> Result<P, String> {
+    let bytes = hex::decode(point.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid hex point {}: {}", point, e))?;
+    P::deserialize_compressed(&bytes[..]).map_err(|e| format!("Invalid point {}: {}", point, e))
+}
+
+/// Reads a base field element stored in little-endian Montgomery form.
+fn read_fq(bytes: &[u8]) -> Result<Fq, String> {
+    let mut limbs = [0u64; FQ_BYTES / 8];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().expect("chunk of 8 bytes"));
+    }
+    let repr = BigInt::new(limbs);
+    if repr >= Fq::MODULUS {
+        return Err("Field element is not reduced".to_string());
+    }
+    Ok(Fq::new_unchecked(repr))
+}
+
+/// Creates a point read from a ptau file, checking that it is in the right subgroup.
+///
+/// The ptau format encodes the point at infinity as all zeros.
+fn check_point<P: SWCurveConfig>(x: P::BaseField, y: P::BaseField) -> Result<Affine<P>, String> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(Affine::zero());
+    }
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err("Point is not in the prime order subgroup".to_string());
+    }
+    Ok(point)
+}
+
+/// Sequential reader over the sections of a ptau file.
+struct PtauReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> PtauReader<'a> {
+    fn take(&mut self, size: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < size {
+            return Err("Unexpected end of ptau file".to_string());
+        }
+        let (head, tail) = self.bytes.split_at(size);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().expect("4 bytes"),
+        ))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(
+            self.take(8)?.try_into().expect("8 bytes"),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::CanonicalSerialize;
+
+    use super::*;
+
+    fn to_hex<P: CanonicalSerialize>(point: &P) -> String {
+        let mut bytes = Vec::new();
+        point.serialize_compressed(&mut bytes).unwrap();
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    fn ethereum_json(srs: &Srs, running_products: &[G1Point], pot_pubkeys: &[G2Point]) -> String {
+        let hex_list = |points: Vec<String>| format!("[\"{}\"]", points.join("\",\""));
+        format!(
+            r#"{{"transcripts":[{{"numG1Powers":{},"numG2Powers":2,"powersOfTau":{{"G1Powers":{},"G2Powers":{}}},"witness":{{"runningProducts":{},"potPubkeys":{},"blsSignatures":[]}}}}]}}"#,
+            srs.g1_points().len(),
+            hex_list(srs.g1_points().iter().map(to_hex).collect()),
+            hex_list(vec![to_hex(&srs.g2()), to_hex(&srs.g2s())]),
+            hex_list(running_products.iter().map(to_hex).collect()),
+            hex_list(pot_pubkeys.iter().map(to_hex).collect()),
+        )
+    }
+
+    fn ptau_bytes(srs: &Srs) -> Vec<u8> {
+        let fq_bytes = |value: &Fq| {
+            value
+                .0
+                 .0
+                .iter()
+                .flat_map(|limb| limb.to_le_bytes())
+                .collect::<Vec<_>>()
+        };
+        let mut header = (FQ_BYTES as u32).to_le_bytes().to_vec();
+        header.extend(Fq::MODULUS.to_bytes_le());
+        let tau_g1 = srs
+            .g1_points()
+            .iter()
+            .flat_map(|p| [fq_bytes(&p.x), fq_bytes(&p.y)].concat())
+            .collect::<Vec<_>>();
+        let tau_g2 = [srs.g2(), srs.g2s()]
+            .iter()
+            .flat_map(|p| {
+                [
+                    fq_bytes(&p.x.c0),
+                    fq_bytes(&p.x.c1),
+                    fq_bytes(&p.y.c0),
+                    fq_bytes(&p.y.c1),
+                ]
+                .concat()
+            })
+            .collect::<Vec<_>>();
+
+        let mut bytes = b"ptau".to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(3u32.to_le_bytes());
+        for (section_type, data) in [(1u32, header), (2, tau_g1), (3, tau_g2)] {
+            bytes.extend(section_type.to_le_bytes());
+            bytes.extend((data.len() as u64).to_le_bytes());
+            bytes.extend(data);
+        }
+        bytes
+    }
+
+    #[test]
+    fn import_ethereum_ceremony() {
+        let rng = &mut rand::thread_rng();
+        let mut srs = Srs::genesis(5);
+        let mut running_products = vec![G1Point::<Bls12_381>::generator()];
+        let mut pot_pubkeys = Vec::new();
+        for _ in 0..3 {
+            pot_pubkeys.push(srs.contribute(rng).update);
+            running_products.push(srs.g1_points()[1]);
+        }
+
+        let json = ethereum_json(&srs, &running_products, &pot_pubkeys);
+        let imported = Srs::from_ethereum_ceremony(&json, 8).unwrap();
+        assert_eq!(imported.g1_points(), srs.g1_points());
+        assert_eq!(imported.g2s(), srs.g2s());
+        assert!(Srs::from_ethereum_ceremony(&json, 16).is_err());
+
+        // A witness that does not lead to the powers is rejected.
+        pot_pubkeys.swap(0, 1);
+        running_products[1] = G1Point::<Bls12_381>::generator();
+        let json = ethereum_json(&srs, &running_products, &pot_pubkeys);
+        assert!(Srs::from_ethereum_ceremony(&json, 8).is_err());
+    }
+
+    #[test]
+    fn import_ptau() {
+        let srs = Srs::new(5);
+        let bytes = ptau_bytes(&srs);
+        let imported = Srs::from_ptau(&bytes).unwrap();
+        assert_eq!(imported.g1_points(), srs.g1_points());
+        assert_eq!(imported.g2(), srs.g2());
+        assert_eq!(imported.g2s(), srs.g2s());
+
+        assert!(Srs::from_ptau(&bytes[..bytes.len() - 1]).is_err());
+        let mut tampered = bytes.clone();
+        tampered[200] ^= 1;
+        assert!(Srs::from_ptau(&tampered).is_err());
+    }
+}
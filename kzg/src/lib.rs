@@ -1,5 +1,11 @@
 pub mod commitment;
+pub mod error;
+pub mod import;
+pub mod mmap;
+pub mod msm;
 pub mod opening;
 pub mod scheme;
+pub mod shplonk;
 pub mod srs;
+pub mod srs_file;
 pub mod types;
@@ -0,0 +1,444 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{One, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Valid, Validate};
+use ark_std::{cfg_chunks, cfg_iter};
+use memmap2::Mmap;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::error::KzgError;
+use crate::msm::msm;
+use crate::opening::powers_of;
+use crate::srs_file::{read_versioned_header, SrsCurve, HEADER_BYTES, SRS_MAGIC};
+use crate::types::{Fr, G1Point, G2Point};
+
+/// Number of G1 points decoded at once when streaming over a mapped SRS.
+///
+/// Tests use small chunks, so that streaming over several chunks is exercised.
+const CHUNK_POINTS: usize = if cfg!(test) { 4 } else { 1 << 16 };
+
+/// Structured Reference String read straight from a memory-mapped file.
+///
+/// The G1 points stay in the file and are decoded chunk by chunk whenever they are
+/// needed, so committing only needs memory for one chunk of points at a time instead
+/// of the whole SRS. Both the raw and the versioned SRS file formats can be mapped, but
+/// the compressed points of the versioned format each cost a square root to decode, so
+/// the points committed with are decoded once and kept, up to the largest commitment.
+pub struct MappedSrs<E: Pairing = Bls12_381> {
+    /// The mapped SRS file.
+    mmap: Mmap,
+    /// Offset in bytes of the first G1 point in the file.
+    offset: usize,
+    /// Number of G1 points.
+    len: usize,
+    /// Whether the points are compressed.
+    compress: Compress,
+    /// Size in bytes of a G1 point in the file.
+    point_size: usize,
+    /// Generator point in G2.
+    g2: G2Point<E>,
+    /// Generator point in G2 multiplied by the secret.
+    g2s_point: G2Point<E>,
+    /// First G1 points already decoded for commitments, if the points are compressed.
+    decoded: RwLock<Vec<G1Point<E>>>,
+}
+
+impl<E: SrsCurve> MappedSrs<E> {
+    /// Maps an SRS file in the versioned or the raw format.
+    ///
+    /// The header, the size of the file and the checksum of versioned files are checked,
+    /// but the G1 points are not decoded. Call `validate` to check them.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the SRS file.
+    ///
+    /// # Returns
+    ///
+    /// The mapped SRS, or an error if the file cannot be mapped or is malformed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("SRS: cannot open file: {}", e))?;
+        // SAFETY: the file must not be modified while it is mapped, as for any SRS file
+        // read by the prover.
+        let mmap =
+            unsafe { Mmap::map(&file) }.map_err(|e| format!("SRS: cannot map file: {}", e))?;
+
+        let (offset, compress, end) = if mmap.starts_with(&SRS_MAGIC) {
+            let (content, _) = read_versioned_header::<E>(&mmap)?;
+            (HEADER_BYTES, Compress::Yes, content.len())
+        } else {
+            (8, Compress::No, mmap.len())
+        };
+
+        if mmap.len() < offset {
+            return Err("SRS: file is truncated".to_string());
+        }
+        let len = u64::from_le_bytes(mmap[offset - 8..offset].try_into().expect("8 bytes"));
+        let point_size = G1Point::<E>::generator().serialized_size(compress);
+        let g2_size = G2Point::<E>::generator().serialized_size(compress);
+        let points_end = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(point_size))
+            .and_then(|size| size.checked_add(offset))
+            .filter(|points_end| points_end.checked_add(2 * g2_size) == Some(end))
+            .ok_or("SRS: file size does not match the number of points")?;
+
+        let mut reader = &mmap[points_end..end];
+        let g2 = G2Point::<E>::deserialize_with_mode(&mut reader, compress, Validate::Yes)
+            .map_err(|e| format!("SRS: invalid G2 point: {}", e))?;
+        let g2s_point = G2Point::<E>::deserialize_with_mode(&mut reader, compress, Validate::Yes)
+            .map_err(|e| format!("SRS: invalid G2 point: {}", e))?;
+
+        Ok(Self {
+            mmap,
+            offset,
+            len: len as usize,
+            compress,
+            point_size,
+            g2,
+            g2s_point,
+            decoded: RwLock::new(Vec::new()),
+        })
+    }
+}
+
+impl<E: Pairing> MappedSrs<E> {
+    /// Returns the number of G1 points.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the SRS has no G1 points.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Decodes a range of G1 points, without checking that they are in the subgroup.
+    ///
+    /// # Parameters
+    ///
+    /// - `range`: The indices of the points to decode.
+    ///
+    /// # Returns
+    ///
+    /// The decoded points, `KzgError::DegreeExceedsSrs` if the range goes past the last
+    /// point, or `KzgError::InvalidSrs` if a point cannot be decoded.
+    pub fn g1_points(&self, range: Range<usize>) -> Result<Vec<G1Point<E>>, KzgError> {
+        if range.end > self.len {
+            return Err(KzgError::DegreeExceedsSrs {
+                degree: range.end - 1,
+                max: self.len.saturating_sub(1),
+            });
+        }
+        let bytes = &self.mmap[self.offset + range.start * self.point_size
+            ..self.offset + range.end * self.point_size];
+        let start = range.start;
+        cfg_chunks!(bytes, self.point_size)
+            .enumerate()
+            .map(|(i, point)| {
+                G1Point::<E>::deserialize_with_mode(point, self.compress, Validate::No).map_err(
+                    |e| {
+                        KzgError::InvalidSrs(format!(
+                            "G1 point {} cannot be decoded: {}",
+                            start + i,
+                            e
+                        ))
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Computes the multi-scalar multiplication `sum(scalars[i] * g1_points[i])`.
+    ///
+    /// Uncompressed points are decoded one chunk at a time, while compressed points are
+    /// decoded once and kept for the next commitments. Scalars beyond the number of points
+    /// are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `scalars`: The scalars to multiply the points with.
+    ///
+    /// # Returns
+    ///
+    /// The resulting point in projective coordinates, or `KzgError::InvalidSrs` if a point
+    /// cannot be decoded.
+    pub fn msm(&self, scalars: &[Fr<E>]) -> Result<E::G1, KzgError> {
+        let size = scalars.len().min(self.len);
+        if matches!(self.compress, Compress::Yes) {
+            let decoded = self.decoded_prefix(size)?;
+            return Ok(msm(&decoded[..size], &scalars[..size]));
+        }
+        (0..size)
+            .step_by(CHUNK_POINTS)
+            .map(|start| {
+                let end = (start + CHUNK_POINTS).min(size);
+                Ok(msm(&self.g1_points(start..end)?, &scalars[start..end]))
+            })
+            .sum()
+    }
+
+    /// Returns the decoded points, decoding the first `size` ones if not done yet.
+    fn decoded_prefix(
+        &self,
+        size: usize,
+    ) -> Result<RwLockReadGuard<'_, Vec<G1Point<E>>>, KzgError> {
+        let decoded = self.decoded.read().unwrap_or_else(PoisonError::into_inner);
+        if decoded.len() >= size {
+            return Ok(decoded);
+        }
+        drop(decoded);
+
+        let mut decoded = self.decoded.write().unwrap_or_else(PoisonError::into_inner);
+        if decoded.len() < size {
+            let points = self.g1_points(decoded.len()..size)?;
+            decoded.extend(points);
+        }
+        drop(decoded);
+        Ok(self.decoded.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Checks that the mapped SRS is well-formed, streaming over its G1 points.
+    ///
+    /// The same checks as `Srs::validate` are done, one chunk of points at a time.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the SRS is valid, otherwise an error describing the problem.
+    pub fn validate(&self) -> Result<(), String> {
+        let g1 = G1Point::<E>::generator();
+        let g2 = G2Point::<E>::generator();
+        if self.is_empty() || self.g2 != g2 || self.g2s_point.is_zero() {
+            return Err("SRS: points are not consecutive powers of a secret".to_string());
+        }
+
+        // Accumulates sum(rho^i * [s^(i+1)]_1) and sum(rho^i * [s^i]_1) over all chunks.
+        let rho = Fr::<E>::rand(&mut rand::thread_rng());
+        let mut shifted = E::G1::zero();
+        let mut unshifted = E::G1::zero();
+        let mut power = Fr::<E>::one();
+        let mut previous: Option<G1Point<E>> = None;
+        for start in (0..self.len).step_by(CHUNK_POINTS) {
+            let end = (start + CHUNK_POINTS).min(self.len);
+            let points = self.g1_points(start..end)?;
+            let invalid = cfg_iter!(points)
+                .map(|point| point.check().is_err())
+                .collect::<Vec<_>>();
+            if let Some(i) = invalid.iter().position(|invalid| *invalid) {
+                return Err(format!(
+                    "SRS: G1 point {} is not in the prime order subgroup",
+                    start + i
+                ));
+            }
+            if start == 0 && (points[0] != g1 || (points.len() > 1 && points[1].is_zero())) {
+                return Err("SRS: points are not consecutive powers of a secret".to_string());
+            }
+
+            // Point `i` is weighted by rho^(i-1) in the shifted sum and by rho^i in the
+            // unshifted one, except for the first and the last point respectively.
+            let chained = previous.into_iter().chain(points).collect::<Vec<_>>();
+            let last = chained.len() - 1;
+            let powers = powers_of(rho, chained.len())
+                .into_iter()
+                .map(|p| p * power)
+                .collect::<Vec<_>>();
+            shifted += msm(&chained[1..], &powers[..last]);
+            unshifted += msm(&chained[..last], &powers[..last]);
+            power = powers[last];
+            previous = Some(chained[last]);
+        }
+
+        if self.len > 1 {
+            let g1_1 = self.g1_points(1..2)?[0];
+            let ok = E::pairing(g1, self.g2s_point) == E::pairing(g1_1, g2)
+                && E::pairing(shifted.into_affine(), g2)
+                    == E::pairing(unshifted.into_affine(), self.g2s_point);
+            if !ok {
+                return Err("SRS: points are not consecutive powers of a secret".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the generator point in G2.
+    pub fn g2(&self) -> G2Point<E> {
+        self.g2
+    }
+
+    /// Returns the generator point in G2 multiplied by the secret.
+    pub fn g2s(&self) -> G2Point<E> {
+        self.g2s_point
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
+
+    use crate::error::KzgError;
+    use crate::scheme::KzgScheme;
+    use crate::srs::Srs;
+    use crate::types::Poly;
+
+    use super::*;
+
+    /// Writes bytes to a temporary file, named after the test.
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("kzg-mmap-{}-{}", std::process::id(), name));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn raw_bytes(srs: &Srs) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        srs.serialize_uncompressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn mapped_commit() {
+        let rng = &mut rand::thread_rng();
+        let srs = Srs::<Bls12_381>::new(14);
+        let raw = temp_file("raw", &raw_bytes(&srs));
+        let versioned = temp_file("versioned", &srs.to_bytes());
+        let poly = Poly::<Bls12_381>::rand(16, rng);
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let evaluations = domain.fft(&poly.coeffs[..16]);
+
+        let scheme = KzgScheme::new(srs.clone())
+            .with_lagrange_basis(domain)
+            .unwrap();
+        for path in [&raw, &versioned] {
+            let mapped = MappedSrs::<Bls12_381>::open(path).unwrap();
+            assert_eq!(mapped.len(), 17);
+            assert_eq!(mapped.g1_points(3..9).unwrap(), srs.g1_points()[3..9]);
+            assert!(mapped.validate().is_ok());
+
+            // Only compressed points are kept once decoded.
+            let scalars = [Fr::<Bls12_381>::from(3); 5];
+            assert_eq!(
+                mapped.msm(&scalars),
+                Ok(msm(&srs.g1_points()[..5], &scalars))
+            );
+            let decoded = mapped.decoded.read().unwrap().len();
+            assert_eq!(decoded, if path == &versioned { 5 } else { 0 });
+
+            let mapped = KzgScheme::new_mapped(mapped)
+                .unwrap()
+                .with_lagrange_basis(domain)
+                .unwrap();
+            assert_eq!(mapped.commit(&poly), scheme.commit(&poly));
+            assert_eq!(
+                mapped.commit_evaluations(&evaluations),
+                scheme.commit_evaluations(&evaluations)
+            );
+            assert_eq!(
+                mapped.commit(&Poly::<Bls12_381>::rand(17, rng)),
+                Err(KzgError::DegreeExceedsSrs {
+                    degree: 17,
+                    max: 16
+                })
+            );
+        }
+        std::fs::remove_file(raw).unwrap();
+        std::fs::remove_file(versioned).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let srs = Srs::<Bls12_381>::new(14);
+
+        // Powers of another secret are only detected by `validate`.
+        let mut bytes = raw_bytes(&srs);
+        let other = raw_bytes(&Srs::<Bls12_381>::new(14));
+        let point = 8 + 9 * 96..8 + 10 * 96;
+        bytes[point.clone()].copy_from_slice(&other[point]);
+        let tampered = temp_file("tampered", &bytes);
+        let mapped = MappedSrs::<Bls12_381>::open(&tampered).unwrap();
+        assert!(mapped.validate().is_err());
+
+        let bytes = raw_bytes(&srs);
+        let truncated = temp_file("truncated", &bytes[..bytes.len() - 1]);
+        assert!(MappedSrs::<Bls12_381>::open(&truncated).is_err());
+
+        let mut bytes = srs.to_bytes();
+        bytes[20] ^= 1;
+        let corrupted = temp_file("corrupted", &bytes);
+        assert!(MappedSrs::<Bls12_381>::open(&corrupted)
+            .err()
+            .unwrap()
+            .contains("checksum"));
+
+        let bn254 = temp_file("bn254", &Srs::<ark_bn254::Bn254>::new(4).to_bytes());
+        assert!(MappedSrs::<Bls12_381>::open(&bn254).is_err());
+        assert!(MappedSrs::<ark_bn254::Bn254>::open(&bn254)
+            .unwrap()
+            .validate()
+            .is_ok());
+
+        for path in [tampered, truncated, corrupted, bn254] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn undecodable_points_are_errors() {
+        let rng = &mut rand::thread_rng();
+        let srs = Srs::<Bls12_381>::new(14);
+
+        // A coordinate larger than the modulus cannot be decoded.
+        let mut bytes = raw_bytes(&srs);
+        bytes[8 + 9 * 96..8 + 10 * 96].fill(0xff);
+        let path = temp_file("undecodable", &bytes);
+        let scheme = KzgScheme::new_mapped(MappedSrs::<Bls12_381>::open(&path).unwrap()).unwrap();
+        assert!(matches!(
+            scheme.commit(&Poly::<Bls12_381>::rand(12, rng)),
+            Err(KzgError::InvalidSrs(_))
+        ));
+        // Points before the undecodable one can still be used.
+        assert!(scheme.commit(&Poly::<Bls12_381>::rand(8, rng)).is_ok());
+        assert!(MappedSrs::<Bls12_381>::open(&path)
+            .unwrap()
+            .validate()
+            .is_err());
+
+        // An SRS without G1 points cannot commit to anything.
+        let mut bytes = 0u64.to_le_bytes().to_vec();
+        srs.g2().serialize_uncompressed(&mut bytes).unwrap();
+        srs.g2s().serialize_uncompressed(&mut bytes).unwrap();
+        let empty = temp_file("empty", &bytes);
+        assert!(matches!(
+            KzgScheme::new_mapped(MappedSrs::<Bls12_381>::open(&empty).unwrap()),
+            Err(KzgError::InvalidSrs(_))
+        ));
+
+        for path in [path, empty] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_accumulates_across_chunks() {
+        // The first point of each chunk is chained with the last point of the previous one.
+        let srs = Srs::<Bls12_381>::new(6);
+        let mut bytes = raw_bytes(&srs);
+        let g1 = G1Point::<Bls12_381>::generator();
+        let scaled = (srs.g1_points()[4] + g1).into_affine();
+        let mut point = Vec::new();
+        scaled.serialize_uncompressed(&mut point).unwrap();
+        bytes[8 + 4 * 96..8 + 5 * 96].copy_from_slice(&point);
+        let path = temp_file("chunks", &bytes);
+        let mapped = MappedSrs::<Bls12_381>::open(&path).unwrap();
+        assert!(mapped.validate().is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
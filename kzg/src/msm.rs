@@ -0,0 +1,291 @@
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::{PrimeField, Zero};
+use ark_std::{cfg_chunks, cfg_into_iter, cfg_iter};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::error::KzgError;
+use crate::types::G1Point;
+
+/// Returns the number of bits in a scalar of the curve.
+fn scalar_bits<A: AffineRepr>() -> usize {
+    A::ScalarField::MODULUS_BIT_SIZE as usize
+}
+
+/// Computes the multi-scalar multiplication `sum(scalars[i] * bases[i])`.
+///
+/// Uses the windowed Pippenger (bucket) method in projective coordinates. If the
+/// slices have different lengths, the extra elements of the longer one are ignored.
+///
+/// # Parameters
+///
+/// - `bases`: The points of the curve.
+/// - `scalars`: The scalars to multiply the points with.
+///
+/// # Returns
+///
+/// The resulting point in projective coordinates.
+pub fn msm<A: AffineRepr>(bases: &[A], scalars: &[A::ScalarField]) -> A::Group {
+    let size = bases.len().min(scalars.len());
+    if size == 0 {
+        return A::Group::zero();
+    }
+
+    let scalars = cfg_iter!(scalars[..size])
+        .map(|scalar| scalar.into_bigint())
+        .collect::<Vec<_>>();
+    let c = window_size(size);
+
+    // The windows are independent, so each one fills its own buckets.
+    let window_starts = (0..scalar_bits::<A>()).step_by(c).collect::<Vec<_>>();
+    let window_sums = cfg_into_iter!(window_starts)
+        .map(|w_start| {
+            let mut buckets = vec![A::Group::zero(); (1 << c) - 1];
+            for (scalar, base) in scalars.iter().zip(&bases[..size]) {
+                let index = window(scalar.as_ref(), w_start, c);
+                if index != 0 {
+                    buckets[index - 1] += base;
+                }
+            }
+            sum_buckets(buckets)
+        })
+        .collect::<Vec<_>>();
+
+    // Combine the windows from the most significant one downwards.
+    window_sums
+        .into_iter()
+        .rev()
+        .fold(A::Group::zero(), |mut total, window_sum| {
+            for _ in 0..c {
+                total.double_in_place();
+            }
+            total + window_sum
+        })
+}
+
+/// Precomputed fixed-base tables for a set of points, in G1 by default.
+///
+/// For a window size `c`, the table stores `2^(c * j) * P` for every base point `P`
+/// and every window `j`. A multi-scalar multiplication over these bases then needs a
+/// single round of bucket accumulation and no doublings, trading memory for speed.
+#[derive(Debug, Clone)]
+pub struct FixedBaseMsm<A: AffineRepr = G1Point> {
+    /// Window size in bits.
+    window: usize,
+    /// Number of windows needed to cover a scalar.
+    num_windows: usize,
+    /// Shifted base points, `num_windows` consecutive entries per base point.
+    table: Vec<A>,
+}
+
+impl<A: AffineRepr> FixedBaseMsm<A> {
+    /// Builds the precomputation tables for the given points.
+    ///
+    /// The tables hold `ceil(MODULUS_BIT_SIZE / window)` points per base point, where
+    /// `MODULUS_BIT_SIZE` is the number of bits of the scalar field.
+    ///
+    /// # Parameters
+    ///
+    /// - `bases`: The points to precompute multiples for.
+    /// - `window`: The window size in bits, must be in `1..=16`.
+    ///
+    /// # Returns
+    ///
+    /// A new `FixedBaseMsm` instance, or `KzgError::InvalidWindow` if the window size is
+    /// out of range.
+    pub fn new(bases: &[A], window: usize) -> Result<Self, KzgError> {
+        if !(1..=16).contains(&window) {
+            return Err(KzgError::InvalidWindow(window));
+        }
+        let num_windows = scalar_bits::<A>().div_ceil(window);
+
+        let table = cfg_iter!(bases)
+            .flat_map(|base| {
+                let mut current = base.into_group();
+                (0..num_windows)
+                    .map(|_| {
+                        let shifted = current;
+                        for _ in 0..window {
+                            current.double_in_place();
+                        }
+                        shifted
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            window,
+            num_windows,
+            table: A::Group::normalize_batch(&table),
+        })
+    }
+
+    /// Returns the number of base points covered by the tables.
+    pub fn len(&self) -> usize {
+        self.table.len() / self.num_windows
+    }
+
+    /// Returns `true` if the tables cover no base points.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Computes `sum(scalars[i] * bases[i])` using the precomputed tables.
+    ///
+    /// Scalars beyond the number of precomputed bases are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `scalars`: The scalars to multiply the precomputed points with.
+    ///
+    /// # Returns
+    ///
+    /// The resulting point in projective coordinates.
+    pub fn msm(&self, scalars: &[A::ScalarField]) -> A::Group {
+        let c = self.window;
+        let size = scalars.len().min(self.len());
+        if size == 0 {
+            return A::Group::zero();
+        }
+
+        // Bucket sums are linear, so chunks of points can be accumulated separately.
+        #[cfg(feature = "parallel")]
+        let chunk_size = size.div_ceil(rayon::current_num_threads());
+        #[cfg(not(feature = "parallel"))]
+        let chunk_size = size;
+
+        let table = &self.table[..size * self.num_windows];
+        cfg_chunks!(scalars[..size], chunk_size)
+            .zip(cfg_chunks!(table, chunk_size * self.num_windows))
+            .map(|(scalars, table)| {
+                let mut buckets = vec![A::Group::zero(); (1 << c) - 1];
+                for (scalar, shifted) in scalars.iter().zip(table.chunks_exact(self.num_windows)) {
+                    let scalar = scalar.into_bigint();
+                    for (j, point) in shifted.iter().enumerate() {
+                        let index = window(scalar.as_ref(), j * c, c);
+                        if index != 0 {
+                            buckets[index - 1] += point;
+                        }
+                    }
+                }
+                sum_buckets(buckets)
+            })
+            .sum()
+    }
+}
+
+/// Chooses the Pippenger window size for an MSM of the given size.
+fn window_size(size: usize) -> usize {
+    if size < 32 {
+        3
+    } else {
+        (size.ilog2() as usize * 69 / 100) + 2
+    }
+}
+
+/// Extracts `c` bits of a little-endian limb representation starting at bit `start`.
+fn window(limbs: &[u64], start: usize, c: usize) -> usize {
+    let limb = start / 64;
+    let offset = start % 64;
+    if limb >= limbs.len() {
+        return 0;
+    }
+
+    let mut bits = limbs[limb] >> offset;
+    if offset + c > 64 && limb + 1 < limbs.len() {
+        bits |= limbs[limb + 1] << (64 - offset);
+    }
+    (bits & ((1 << c) - 1)) as usize
+}
+
+/// Reduces the buckets to `sum((i + 1) * buckets[i])` with a running sum.
+fn sum_buckets<G: Group>(buckets: Vec<G>) -> G {
+    let mut running_sum = G::zero();
+    let mut result = G::zero();
+    for bucket in buckets.into_iter().rev() {
+        running_sum += bucket;
+        result += running_sum;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Mul;
+
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ff::{BigInteger, UniformRand};
+
+    use super::*;
+
+    fn naive_msm(bases: &[G1Point], scalars: &[Fr]) -> G1Projective {
+        bases
+            .iter()
+            .zip(scalars)
+            .map(|(base, scalar)| base.mul(scalar))
+            .sum()
+    }
+
+    fn random_bases(size: usize) -> Vec<G1Point> {
+        let rng = &mut rand::thread_rng();
+        let points = (0..size)
+            .map(|_| G1Point::<Bls12_381>::generator().mul(Fr::rand(rng)))
+            .collect::<Vec<_>>();
+        G1Projective::normalize_batch(&points)
+    }
+
+    #[test]
+    fn msm_matches_naive() {
+        let rng = &mut rand::thread_rng();
+        for size in [0, 1, 2, 31, 32, 100] {
+            let bases = random_bases(size);
+            let scalars = (0..size).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            assert_eq!(msm(&bases, &scalars), naive_msm(&bases, &scalars));
+        }
+    }
+
+    #[test]
+    fn msm_edge_scalars() {
+        let bases = random_bases(4);
+        let scalars = [Fr::zero(), Fr::from(1), -Fr::from(1), Fr::from(u64::MAX)];
+        assert_eq!(msm(&bases, &scalars), naive_msm(&bases, &scalars));
+    }
+
+    #[test]
+    fn fixed_base_msm_matches_naive() {
+        let rng = &mut rand::thread_rng();
+        let bases = random_bases(20);
+        for window in [1, 4, 7, 16] {
+            let table = FixedBaseMsm::new(&bases, window).unwrap();
+            assert_eq!(table.len(), bases.len());
+
+            let scalars = (0..15).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            assert_eq!(table.msm(&scalars), naive_msm(&bases, &scalars));
+        }
+    }
+
+    #[test]
+    fn fixed_base_msm_rejects_window() {
+        let bases = random_bases(2);
+        for window in [0, 17] {
+            assert_eq!(
+                FixedBaseMsm::new(&bases, window).unwrap_err(),
+                KzgError::InvalidWindow(window)
+            );
+        }
+    }
+
+    #[test]
+    fn window_crosses_limbs() {
+        let scalar = (-Fr::from(1)).into_bigint();
+        let limbs = scalar.as_ref();
+        for start in [60, 62, 63, 64, 250, 254] {
+            let expected = (0..5)
+                .filter(|i| start + i < 256 && scalar.get_bit(start + i))
+                .map(|i| 1 << i)
+                .sum::<usize>();
+            assert_eq!(window(limbs, start, 5), expected);
+        }
+    }
+}
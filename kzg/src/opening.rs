@@ -1,30 +1,103 @@
 use std::ops::Add;
 
-use ark_bls12_381::Fr;
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
 
-use crate::types::G1Point;
+use crate::msm::msm;
+use crate::types::{Fr, G1Point};
 
 /// Represents an opening at a point with its corresponding evaluation.
 ///
 /// `KzgOpening` encapsulates a `G1Point` representing the point and an `Fr` representing the evaluation.
 #[derive(Debug, Clone)]
-pub struct KzgOpening(pub G1Point, pub Fr);
+pub struct KzgOpening<E: Pairing = Bls12_381>(pub G1Point<E>, pub Fr<E>);
 
-impl KzgOpening {
+/// Represents an opening of a hiding commitment at a point.
+///
+/// `KzgHidingOpening` holds the witness point, the evaluation of the polynomial and the
+/// evaluation of the blinding polynomial.
+#[derive(Debug, Clone)]
+pub struct KzgHidingOpening<E: Pairing = Bls12_381>(pub G1Point<E>, pub Fr<E>, pub Fr<E>);
+
+/// Represents a multi-point opening of several polynomials.
+///
+/// `KzgMultiOpening` holds the two witness points `W` and `W'` of a SHPLONK opening.
+#[derive(Debug, Clone)]
+pub struct KzgMultiOpening<E: Pairing = Bls12_381>(pub G1Point<E>, pub G1Point<E>);
+
+impl<E: Pairing> KzgOpening<E> {
+    /// Retrieves the evaluation associated with the opening.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation (`Fr`) of the opening.
+    pub fn eval(self) -> Fr<E> {
+        self.1
+    }
+
+    /// Aggregates openings of several polynomials at the same point into one opening.
+    ///
+    /// The `i`-th opening is weighted by `challenge^i`, which matches the opening
+    /// produced by `KzgScheme::batch_open` for the same polynomials and challenge.
+    ///
+    /// # Parameters
+    ///
+    /// - `openings`: The openings to aggregate, all at the same point.
+    /// - `challenge`: The challenge used to combine the openings.
+    ///
+    /// # Returns
+    ///
+    /// The aggregated opening.
+    pub fn aggregate(openings: &[KzgOpening<E>], challenge: Fr<E>) -> Self {
+        let powers = powers_of(challenge, openings.len());
+        let witnesses = openings.iter().map(|opening| opening.0).collect::<Vec<_>>();
+        let witness = msm(&witnesses, &powers).into_affine();
+        let eval = openings
+            .iter()
+            .zip(&powers)
+            .map(|(opening, power)| opening.1 * power)
+            .sum();
+        Self(witness, eval)
+    }
+}
+
+impl<E: Pairing> KzgHidingOpening<E> {
     /// Retrieves the evaluation associated with the opening.
     ///
     /// # Returns
     ///
     /// The evaluation (`Fr`) of the opening.
-    pub fn eval(self) -> Fr {
+    pub fn eval(&self) -> Fr<E> {
         self.1
     }
+
+    /// Retrieves the evaluation of the blinding polynomial associated with the opening.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation (`Fr`) of the blinding polynomial.
+    pub fn blinding_eval(&self) -> Fr<E> {
+        self.2
+    }
+}
+
+/// Returns `[1, challenge, challenge^2, ..., challenge^(count - 1)]`.
+pub(crate) fn powers_of<F: Field>(challenge: F, count: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(count);
+    let mut power = F::one();
+    for _ in 0..count {
+        powers.push(power);
+        power *= challenge;
+    }
+    powers
 }
 
-impl Add for KzgOpening {
+impl<E: Pairing> Add for KzgOpening<E> {
     type Output = Self;
 
-    /// Combines two openings by adding their evaluations and doubling the witness point.
+    /// Combines two openings at the same point by adding their evaluations and witness points.
     ///
     /// # Parameters
     ///
@@ -37,8 +110,8 @@ impl Add for KzgOpening {
     fn add(self, rhs: Self) -> Self::Output {
         // Add evaluations
         let eval = self.1 + rhs.1;
-        // Double the witness point
-        let witness = self.0 + self.0;
-        Self(witness.into(), eval)
+        // Add the witness points
+        let witness = self.0 + rhs.0;
+        Self(witness.into_affine(), eval)
     }
 }
@@ -1,23 +1,41 @@
 use std::fmt::{Debug, Display};
 use std::ops::{Add, Mul, Neg, Sub};
 
-use ark_bls12_381::{Bls12_381, Fr};
+use ark_bls12_381::Bls12_381;
 use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_poly::{DenseUVPolynomial, Polynomial};
+use ark_ff::{One, Zero};
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+use ark_std::cfg_iter;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::commitment::KzgCommitment;
-use crate::opening::KzgOpening;
-use crate::srs::Srs;
-use crate::types::{G1Point, G2Point, Poly};
+use crate::error::KzgError;
+use crate::mmap::MappedSrs;
+use crate::msm::{self, FixedBaseMsm};
+use crate::opening::{powers_of, KzgHidingOpening, KzgOpening};
+use crate::srs::{HidingSrs, Srs};
+use crate::types::{Fr, G1Point, G2Point, Poly};
 
 /// Implements the KZG polynomial commitment scheme.
 ///
 /// The `KzgScheme` struct provides methods for committing to polynomials, opening commitments,
 /// and verifying openings.
-pub struct KzgScheme(Srs);
+pub struct KzgScheme<E: Pairing = Bls12_381> {
+    /// The structured reference string (SRS) used in the scheme.
+    srs: Srs<E>,
+    /// Optional fixed-base precomputation tables over the G1 points of the SRS.
+    precomputed: Option<FixedBaseMsm<G1Point<E>>>,
+    /// Optional commitments to the Lagrange basis of a domain.
+    lagrange_basis: Option<Vec<G1Point<E>>>,
+    /// Optional points of the blinding generator, used for hiding commitments.
+    h_points: Option<Vec<G1Point<E>>>,
+    /// Optional memory-mapped SRS, from which the G1 points are read instead of `srs`.
+    mapped: Option<MappedSrs<E>>,
+}
 
-impl KzgScheme {
+impl<E: Pairing> KzgScheme<E> {
     /// Creates a new instance of `KzgScheme` with the given structured reference string (SRS).
     ///
     /// # Parameters
@@ -27,12 +45,134 @@ impl KzgScheme {
     /// # Returns
     ///
     /// A new instance of `KzgScheme`.
-    pub fn new(srs: Srs) -> Self {
-        Self(srs)
+    pub fn new(srs: Srs<E>) -> Self {
+        Self {
+            srs,
+            precomputed: None,
+            lagrange_basis: None,
+            h_points: None,
+            mapped: None,
+        }
+    }
+
+    /// Creates a new instance of `KzgScheme` with fixed-base precomputation tables.
+    ///
+    /// The tables hold `ceil(MODULUS_BIT_SIZE / window)` points per SRS point, where
+    /// `MODULUS_BIT_SIZE` is the number of bits of the scalar field, so this speeds up
+    /// every commitment at the cost of memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `srs`: The structured reference string (SRS) used in the scheme.
+    /// - `window`: The window size in bits of the precomputation tables, in `1..=16`.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `KzgScheme`, or `KzgError::InvalidWindow` if the window size is
+    /// out of range.
+    pub fn new_with_precomputation(srs: Srs<E>, window: usize) -> Result<Self, KzgError> {
+        let precomputed = FixedBaseMsm::new(srs.g1_points(), window)?;
+        Ok(Self {
+            srs,
+            precomputed: Some(precomputed),
+            lagrange_basis: None,
+            h_points: None,
+            mapped: None,
+        })
+    }
+
+    /// Creates a new instance of `KzgScheme` that also supports hiding commitments.
+    ///
+    /// # Parameters
+    ///
+    /// - `srs`: The hiding structured reference string used in the scheme.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `KzgScheme`.
+    pub fn new_hiding(srs: HidingSrs<E>) -> Self {
+        let (srs, h_points) = srs.into_parts();
+        Self {
+            h_points: Some(h_points),
+            ..Self::new(srs)
+        }
+    }
+
+    /// Creates a new instance of `KzgScheme` that commits straight from a memory-mapped SRS.
+    ///
+    /// The G1 points are decoded from the mapped file chunk by chunk on each commitment,
+    /// so the SRS is never fully loaded in memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `mapped`: The memory-mapped SRS, which should have been validated.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `KzgScheme`, or `KzgError::InvalidSrs` if the mapped SRS has no
+    /// G1 points or its first points are invalid.
+    pub fn new_mapped(mapped: MappedSrs<E>) -> Result<Self, KzgError> {
+        if mapped.is_empty() {
+            return Err(KzgError::InvalidSrs(
+                "the mapped SRS has no G1 points".to_string(),
+            ));
+        }
+        let srs = Srs::from_points(mapped.g1_points(0..1)?, mapped.g2(), mapped.g2s())
+            .map_err(KzgError::InvalidSrs)?;
+        Ok(Self {
+            mapped: Some(mapped),
+            ..Self::new(srs)
+        })
+    }
+
+    /// Precomputes the Lagrange basis of a domain, so that `commit_evaluations` can be used.
+    ///
+    /// # Parameters
+    ///
+    /// - `domain`: The evaluation domain of the polynomials committed from evaluations.
+    ///
+    /// # Returns
+    ///
+    /// The `KzgScheme` with the Lagrange basis of the domain, or `KzgError::DegreeExceedsSrs`
+    /// if the SRS has fewer points than the domain.
+    pub fn with_lagrange_basis(
+        mut self,
+        domain: GeneralEvaluationDomain<Fr<E>>,
+    ) -> Result<Self, KzgError> {
+        self.lagrange_basis = Some(self.lagrange_basis(domain)?);
+        Ok(self)
+    }
+
+    /// Computes the commitments to the Lagrange basis of a domain, to be kept by the caller.
+    ///
+    /// # Parameters
+    ///
+    /// - `domain`: The evaluation domain of the polynomials committed from evaluations.
+    ///
+    /// # Returns
+    ///
+    /// The commitment to each Lagrange polynomial of the domain, or
+    /// `KzgError::DegreeExceedsSrs` if the SRS has fewer points than the domain.
+    pub fn lagrange_basis(
+        &self,
+        domain: GeneralEvaluationDomain<Fr<E>>,
+    ) -> Result<Vec<G1Point<E>>, KzgError> {
+        match &self.mapped {
+            Some(mapped) => {
+                let size = domain.size().min(mapped.len());
+                let srs = Srs::<E>::from_points_unchecked(
+                    mapped.g1_points(0..size)?,
+                    mapped.g2(),
+                    mapped.g2s(),
+                );
+                srs.to_lagrange_basis(domain)
+            }
+            None => self.srs.to_lagrange_basis(domain),
+        }
     }
 }
 
-impl KzgScheme {
+impl<E: Pairing> KzgScheme<E> {
     /// Commits to a polynomial using the KZG scheme.
     ///
     /// # Parameters
@@ -41,10 +181,31 @@ impl KzgScheme {
     ///
     /// # Returns
     ///
-    /// The commitment to the polynomial.
-    pub fn commit(&self, polynomial: &Poly) -> KzgCommitment {
-        let commitment = self.evaluate_in_s(polynomial);
-        KzgCommitment(commitment)
+    /// The commitment to the polynomial, or `KzgError::DegreeExceedsSrs` if the SRS is too small.
+    pub fn commit(&self, polynomial: &Poly<E>) -> Result<KzgCommitment<E>, KzgError> {
+        let commitment = self.evaluate_in_s(polynomial)?;
+        Ok(KzgCommitment(commitment))
+    }
+
+    /// Commits to several polynomials, in parallel with the `parallel` feature.
+    ///
+    /// # Parameters
+    ///
+    /// - `polynomials`: The polynomials to be committed to.
+    ///
+    /// # Returns
+    ///
+    /// The commitments to the polynomials, in the same order, or the first error.
+    pub fn commit_all<const N: usize>(
+        &self,
+        polynomials: [&Poly<E>; N],
+    ) -> Result<[KzgCommitment<E>; N], KzgError> {
+        let commitments = cfg_iter!(polynomials)
+            .map(|polynomial| self.commit(polynomial))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(commitments
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("one commitment per polynomial")))
     }
 
     /// Commits to a parameter using the KZG scheme.
@@ -55,25 +216,93 @@ impl KzgScheme {
     ///
     /// # Returns
     ///
-    /// The commitment to the parameter.
-    pub fn commit_para(&self, para: Fr) -> KzgCommitment {
-        let g1_0 = *self.0.g1_points().first().unwrap();
-        let commitment = g1_0.mul(para).into();
-        KzgCommitment(commitment)
+    /// The commitment to the parameter, or `KzgError::DegreeExceedsSrs` if the SRS is empty.
+    pub fn commit_para(&self, para: Fr<E>) -> Result<KzgCommitment<E>, KzgError> {
+        self.check_degree(0)?;
+        let commitment = self.msm(&[para])?.into_affine();
+        Ok(KzgCommitment(commitment))
+    }
+
+    /// Commits to a polynomial given by its evaluations over a domain.
+    ///
+    /// This avoids interpolating the polynomial. Missing evaluations at the end of the
+    /// domain are treated as zeros.
+    ///
+    /// # Parameters
+    ///
+    /// - `evaluations`: The evaluations of the polynomial over the domain passed to
+    ///   `with_lagrange_basis`.
+    ///
+    /// # Returns
+    ///
+    /// The commitment to the polynomial, or an error if no Lagrange basis was precomputed
+    /// or there are more evaluations than elements in the domain.
+    pub fn commit_evaluations(&self, evaluations: &[Fr<E>]) -> Result<KzgCommitment<E>, KzgError> {
+        let basis = self
+            .lagrange_basis
+            .as_ref()
+            .ok_or(KzgError::MissingLagrangeBasis)?;
+        if evaluations.len() > basis.len() {
+            return Err(KzgError::DegreeExceedsSrs {
+                degree: evaluations.len() - 1,
+                max: basis.len().saturating_sub(1),
+            });
+        }
+
+        Ok(KzgCommitment(msm::msm(basis, evaluations).into_affine()))
+    }
+
+    /// Commits to several polynomials given by their evaluations, in parallel with the
+    /// `parallel` feature.
+    ///
+    /// # Parameters
+    ///
+    /// - `evaluations`: The evaluations of each polynomial over the domain passed to
+    ///   `with_lagrange_basis`.
+    ///
+    /// # Returns
+    ///
+    /// The commitments to the polynomials, in the same order, or the first error.
+    pub fn commit_evaluations_all<const N: usize>(
+        &self,
+        evaluations: [&[Fr<E>]; N],
+    ) -> Result<[KzgCommitment<E>; N], KzgError> {
+        let commitments = cfg_iter!(evaluations)
+            .map(|evaluations| self.commit_evaluations(evaluations))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(commitments
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("one commitment per polynomial")))
     }
 
-    fn evaluate_in_s(&self, polynomial: &Poly) -> G1Point {
-        let g1_points = self.0.g1_points();
-        assert!(g1_points.len() > polynomial.degree());
+    fn evaluate_in_s(&self, polynomial: &Poly<E>) -> Result<G1Point<E>, KzgError> {
+        self.check_degree(polynomial.degree())?;
+        Ok(self.msm(&polynomial.coeffs)?.into_affine())
+    }
 
-        let poly = polynomial.coeffs.iter();
-        let g1_points = g1_points.into_iter();
-        let point: G1Point = poly
-            .zip(g1_points)
-            .map(|(cof, s)| s.mul(cof).into_affine())
-            .reduce(|acc, e| acc.add(e).into_affine())
-            .unwrap_or(G1Point::zero());
-        point
+    /// Checks that the SRS has enough G1 points to commit to a polynomial of the given degree.
+    fn check_degree(&self, degree: usize) -> Result<(), KzgError> {
+        let len = match &self.mapped {
+            Some(mapped) => mapped.len(),
+            None => self.srs.g1_points().len(),
+        };
+        if degree >= len {
+            return Err(KzgError::DegreeExceedsSrs {
+                degree,
+                max: len.saturating_sub(1),
+            });
+        }
+        Ok(())
+    }
+
+    /// Computes `sum(scalars[i] * g1_points[i])`, using the precomputed tables or the
+    /// mapped SRS if available.
+    fn msm(&self, scalars: &[Fr<E>]) -> Result<E::G1, KzgError> {
+        match (&self.precomputed, &self.mapped) {
+            (Some(precomputed), _) => Ok(precomputed.msm(scalars)),
+            (None, Some(mapped)) => mapped.msm(scalars),
+            (None, None) => Ok(msm::msm(self.srs.g1_points(), scalars)),
+        }
     }
 
     /// Opens a commitment at a specified point.
@@ -85,17 +314,59 @@ impl KzgScheme {
     ///
     /// # Returns
     ///
-    /// The opening at the specified point.
-    pub fn open(&self, mut polynomial: Poly, z: impl Into<Fr>) -> KzgOpening {
-        let z = z.into();
+    /// The opening at the specified point, or an error if the polynomial is empty or
+    /// its degree exceeds the SRS.
+    pub fn open(
+        &self,
+        polynomial: Poly<E>,
+        z: impl Into<Fr<E>>,
+    ) -> Result<KzgOpening<E>, KzgError> {
+        let (quotient, evaluation_at_z) = Self::divide_at(polynomial, z.into())?;
+        let opening = self.evaluate_in_s(&quotient)?;
+
+        Ok(KzgOpening(opening, evaluation_at_z))
+    }
+
+    /// Computes `(polynomial(X) - polynomial(z)) / (X - z)` and `polynomial(z)`.
+    fn divide_at(mut polynomial: Poly<E>, z: Fr<E>) -> Result<(Poly<E>, Fr<E>), KzgError> {
         let evaluation_at_z = polynomial.evaluate(&z);
-        let first = polynomial.coeffs.first_mut().expect("at least 1");
+        let first = polynomial
+            .coeffs
+            .first_mut()
+            .ok_or(KzgError::EmptyPolynomial)?;
         *first -= evaluation_at_z;
-        let root = Poly::from_coefficients_slice(&[-z, 1.into()]);
-        let new_poly = &polynomial / &root;
-        let opening = self.evaluate_in_s(&new_poly);
+        let root = Poly::<E>::from_coefficients_slice(&[-z, Fr::<E>::one()]);
+        Ok((&polynomial / &root, evaluation_at_z))
+    }
 
-        KzgOpening(opening, evaluation_at_z)
+    /// Opens several polynomials at the same point with a single witness.
+    ///
+    /// The polynomials are combined as `sum(challenge^i * polynomials[i])` and the
+    /// combination is opened at `z`.
+    ///
+    /// # Parameters
+    ///
+    /// - `polynomials`: The polynomials to be opened.
+    /// - `z`: The point at which the polynomials are opened.
+    /// - `challenge`: The challenge used to combine the polynomials.
+    ///
+    /// # Returns
+    ///
+    /// The opening of the combined polynomial, holding the combined evaluation.
+    pub fn batch_open(
+        &self,
+        polynomials: &[Poly<E>],
+        z: impl Into<Fr<E>>,
+        challenge: Fr<E>,
+    ) -> Result<KzgOpening<E>, KzgError> {
+        let mut combined = Poly::<E>::zero();
+        for (polynomial, power) in polynomials
+            .iter()
+            .zip(powers_of(challenge, polynomials.len()))
+        {
+            combined += (power, polynomial);
+        }
+        self.open(combined, z)
     }
 
     /// Verifies the correctness of an opening.
@@ -108,21 +379,184 @@ impl KzgScheme {
     ///
     /// # Returns
     ///
-    /// `true` if the opening is valid, otherwise `false`.
+    /// `Ok(())` if the opening is valid, otherwise `KzgError::InvalidOpening`.
     pub fn verify(
         &self,
-        commitment: &KzgCommitment,
-        opening: &KzgOpening,
-        z: impl Into<Fr> + Debug + Display,
-    ) -> bool {
+        commitment: &KzgCommitment<E>,
+        opening: &KzgOpening<E>,
+        z: impl Into<Fr<E>> + Debug + Display,
+    ) -> Result<(), KzgError> {
         let y = opening.1;
-        let g2s = self.0.g2s();
-        let g2 = self.0.g2();
-        let a = g2s.sub(g2.mul(z.into()).into_affine());
-        let b = commitment.0.sub(G1Point::generator().mul(y).into_affine());
-        let pairing1 = Bls12_381::pairing(opening.0, a);
-        let pairing2 = Bls12_381::pairing(b, g2);
-        pairing1 == pairing2
+        let g2s = self.srs.g2s();
+        let g2 = self.srs.g2();
+        let a = g2s.into_group() - g2.mul(z.into());
+        let b = commitment.0.into_group() - self.g1().mul(y);
+        if !E::multi_pairing([opening.0.into_group(), -b], [a, g2.into_group()]).is_zero() {
+            return Err(KzgError::InvalidOpening);
+        }
+        Ok(())
+    }
+
+    /// Verifies an opening produced by `batch_open`.
+    ///
+    /// # Parameters
+    ///
+    /// - `commitments`: The commitments to the opened polynomials.
+    /// - `evaluations`: The claimed evaluations of the polynomials at `z`.
+    /// - `z`: The point at which the polynomials were opened.
+    /// - `challenge`: The challenge used to combine the polynomials.
+    /// - `opening`: The batched opening to be verified.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the opening is valid for every commitment and evaluation, otherwise an error.
+    pub fn batch_verify(
+        &self,
+        commitments: &[KzgCommitment<E>],
+        evaluations: &[Fr<E>],
+        z: impl Into<Fr<E>> + Debug + Display,
+        challenge: Fr<E>,
+        opening: &KzgOpening<E>,
+    ) -> Result<(), KzgError> {
+        if commitments.len() != evaluations.len() {
+            return Err(KzgError::LengthMismatch);
+        }
+
+        let powers = powers_of(challenge, commitments.len());
+        let points = commitments
+            .iter()
+            .map(|commitment| commitment.0)
+            .collect::<Vec<_>>();
+        let commitment = KzgCommitment(msm::msm(&points, &powers).into_affine());
+        let evaluation: Fr<E> = evaluations
+            .iter()
+            .zip(&powers)
+            .map(|(evaluation, power)| *evaluation * power)
+            .sum();
+        if evaluation != opening.1 {
+            return Err(KzgError::InvalidOpening);
+        }
+
+        self.verify(&commitment, opening, z)
+    }
+
+    /// Commits to a polynomial with a blinding polynomial, so that the commitment hides it.
+    ///
+    /// The commitment equals `[polynomial(s)]G + [blinding(s)]H`, where `H` is the blinding
+    /// generator of the hiding SRS.
+    ///
+    /// # Parameters
+    ///
+    /// - `polynomial`: The polynomial to be committed to.
+    /// - `blinding`: The random blinding polynomial, of at most the same degree.
+    ///
+    /// # Returns
+    ///
+    /// The hiding commitment, or an error if the scheme has no blinding points or either
+    /// polynomial exceeds the SRS.
+    pub fn commit_hiding(
+        &self,
+        polynomial: &Poly<E>,
+        blinding: &Poly<E>,
+    ) -> Result<KzgCommitment<E>, KzgError> {
+        let commitment =
+            self.evaluate_in_s(polynomial)?.into_group() + self.evaluate_in_h(blinding)?;
+        Ok(KzgCommitment(commitment.into_affine()))
+    }
+
+    /// Opens a hiding commitment at a specified point.
+    ///
+    /// # Parameters
+    ///
+    /// - `polynomial`: The committed polynomial.
+    /// - `blinding`: The blinding polynomial passed to `commit_hiding`.
+    /// - `z`: The point at which the polynomials are opened.
+    ///
+    /// # Returns
+    ///
+    /// The opening at the specified point, carrying the evaluation of both polynomials.
+    pub fn open_hiding(
+        &self,
+        polynomial: Poly<E>,
+        blinding: Poly<E>,
+        z: impl Into<Fr<E>>,
+    ) -> Result<KzgHidingOpening<E>, KzgError> {
+        let z = z.into();
+        let (quotient, evaluation_at_z) = Self::divide_at(polynomial, z)?;
+        let (blinding_quotient, blinding_at_z) = Self::divide_at(blinding, z)?;
+        let witness =
+            self.evaluate_in_s(&quotient)?.into_group() + self.evaluate_in_h(&blinding_quotient)?;
+
+        Ok(KzgHidingOpening(
+            witness.into_affine(),
+            evaluation_at_z,
+            blinding_at_z,
+        ))
+    }
+
+    /// Verifies the correctness of an opening of a hiding commitment.
+    ///
+    /// # Parameters
+    ///
+    /// - `commitment`: The hiding commitment to be verified.
+    /// - `opening`: The opening to be verified.
+    /// - `z`: The point at which the polynomials were opened.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the opening is valid, otherwise an error.
+    pub fn verify_hiding(
+        &self,
+        commitment: &KzgCommitment<E>,
+        opening: &KzgHidingOpening<E>,
+        z: impl Into<Fr<E>> + Debug + Display,
+    ) -> Result<(), KzgError> {
+        let h = self
+            .h_points
+            .as_ref()
+            .and_then(|points| points.first())
+            .ok_or(KzgError::MissingHidingPoints)?;
+        let g2s = self.srs.g2s();
+        let g2 = self.srs.g2();
+        let a = g2s.into_group() - g2.mul(z.into());
+        let b = commitment.0.into_group() - self.g1().mul(opening.1) - h.mul(opening.2);
+        if !E::multi_pairing([opening.0.into_group(), -b], [a, g2.into_group()]).is_zero() {
+            return Err(KzgError::InvalidOpening);
+        }
+        Ok(())
+    }
+
+    /// Computes `sum(coeffs[i] * h_points[i])` for a blinding polynomial.
+    fn evaluate_in_h(&self, blinding: &Poly<E>) -> Result<E::G1, KzgError> {
+        let h_points = self
+            .h_points
+            .as_ref()
+            .ok_or(KzgError::MissingHidingPoints)?;
+        if blinding.degree() >= h_points.len() {
+            return Err(KzgError::DegreeExceedsSrs {
+                degree: blinding.degree(),
+                max: h_points.len().saturating_sub(1),
+            });
+        }
+        Ok(msm::msm(h_points, &blinding.coeffs))
+    }
+
+    /// Extracts the points of the SRS needed by a verifier that is given the commitments.
+    ///
+    /// # Returns
+    ///
+    /// An SRS holding only the first G1 point, the generator in G2 and the secret in G2.
+    pub fn verifier_srs(&self) -> Srs<E> {
+        self.srs.verifier_subset()
+    }
+
+    /// Returns the first G1 point of the SRS, the generator the powers are taken of.
+    ///
+    /// # Returns
+    ///
+    /// The generator point in G1.
+    pub fn g1(&self) -> G1Point<E> {
+        self.srs.g1_points()[0]
     }
 
     /// Returns the generator point in G2.
@@ -130,8 +564,8 @@ impl KzgScheme {
     /// # Returns
     ///
     /// The generator point in G2.
-    pub fn g2(&self) -> G2Point {
-        self.0.g2()
+    pub fn g2(&self) -> G2Point<E> {
+        self.srs.g2()
     }
 
     /// Returns the generator point in G2 multiplied by the secret.
@@ -139,21 +573,21 @@ impl KzgScheme {
     /// # Returns
     ///
     /// The generator point in G2 multiplied by the secret.
-    pub fn g2s(&self) -> G2Point {
-        self.0.g2s()
+    pub fn g2s(&self) -> G2Point<E> {
+        self.srs.g2s()
     }
 }
 
-impl Add for KzgCommitment {
+impl<E: Pairing> Add for KzgCommitment<E> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
         let commitment = self.0 + rhs.0;
-        Self(commitment.into())
+        Self(commitment.into_affine())
     }
 }
 
-impl Sub for KzgCommitment {
+impl<E: Pairing> Sub for KzgCommitment<E> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -161,29 +595,29 @@ impl Sub for KzgCommitment {
     }
 }
 
-impl Mul<Fr> for KzgCommitment {
+impl<E: Pairing> Mul<Fr<E>> for KzgCommitment<E> {
     type Output = Self;
 
-    fn mul(self, rhs: Fr) -> Self::Output {
+    fn mul(self, rhs: Fr<E>) -> Self::Output {
         let element = self.0.mul(rhs);
-        Self(element.into())
+        Self(element.into_affine())
     }
 }
 
-impl Mul<Fr> for &KzgCommitment {
-    type Output = KzgCommitment;
+impl<E: Pairing> Mul<Fr<E>> for &KzgCommitment<E> {
+    type Output = KzgCommitment<E>;
 
-    fn mul(self, rhs: Fr) -> Self::Output {
+    fn mul(self, rhs: Fr<E>) -> Self::Output {
         let element = self.0.mul(rhs);
-        KzgCommitment(element.into())
+        KzgCommitment(element.into_affine())
     }
 }
 
-impl Neg for KzgCommitment {
+impl<E: Pairing> Neg for KzgCommitment<E> {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
-        let point = self.0;
-        Self(-point)
+        let point = self.0.into_group();
+        Self((-point).into_affine())
     }
 }
@@ -0,0 +1,283 @@
+use std::ops::Mul;
+
+use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
+use ark_ff::{PrimeField, Zero};
+use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial};
+use ark_poly::{DenseUVPolynomial, Polynomial};
+
+use crate::commitment::KzgCommitment;
+use crate::error::KzgError;
+use crate::msm::msm;
+use crate::opening::{powers_of, KzgMultiOpening};
+use crate::scheme::KzgScheme;
+use crate::types::{Fr, Poly};
+
+impl<E: Pairing> KzgScheme<E> {
+    /// Opens several polynomials, each at its own set of points, with two witnesses.
+    ///
+    /// This follows the single-pairing variant of BDFG20 (SHPLONK). The polynomials are
+    /// combined with powers of `gamma`, the first witness `W` is committed, and the
+    /// evaluation challenge is then derived from `W` through `challenge`.
+    ///
+    /// # Parameters
+    ///
+    /// - `polynomials`: The polynomials to be opened.
+    /// - `points`: For each polynomial, the points at which it is opened.
+    /// - `gamma`: The challenge used to combine the polynomials.
+    /// - `challenge`: Derives the evaluation challenge from the commitment to `W`.
+    ///
+    /// # Returns
+    ///
+    /// The multi-point opening `(W, W')`, or an error if a polynomial has no set of points
+    /// or a degree exceeding the SRS.
+    pub fn multi_open(
+        &self,
+        polynomials: &[Poly<E>],
+        points: &[Vec<Fr<E>>],
+        gamma: Fr<E>,
+        challenge: impl FnOnce(&KzgCommitment<E>) -> Fr<E>,
+    ) -> Result<KzgMultiOpening<E>, KzgError> {
+        if polynomials.len() != points.len() {
+            return Err(KzgError::LengthMismatch);
+        }
+        let powers = powers_of(gamma, polynomials.len());
+
+        // h(X) = sum(gamma^i * (f_i(X) - r_i(X)) / Z_{S_i}(X))
+        let mut h = Poly::<E>::zero();
+        for ((polynomial, set), power) in polynomials.iter().zip(points).zip(&powers) {
+            let vanishing = DenseOrSparsePolynomial::from(vanishing_poly(set));
+            let (quotient, _) = DenseOrSparsePolynomial::from(polynomial)
+                .divide_with_q_and_r(&vanishing)
+                .expect("vanishing polynomial is never zero");
+            h += (*power, &quotient);
+        }
+        let w = self.commit(&h)?;
+        let z = challenge(&w);
+
+        // L(X) = sum(gamma^i * Z_{T \ S_i}(z) * (f_i(X) - r_i(z))) - Z_T(z) * h(X)
+        let all_points = union(points);
+        let mut l = h.mul(-evaluate_vanishing(&all_points, z));
+        for ((polynomial, set), power) in polynomials.iter().zip(points).zip(&powers) {
+            let evaluations = set
+                .iter()
+                .map(|point| polynomial.evaluate(point))
+                .collect::<Vec<_>>();
+            let factor = *power * evaluate_vanishing(&difference(&all_points, set), z);
+            l += (factor, polynomial);
+            l -= &Poly::<E>::from_coefficients_vec(vec![
+                factor * interpolate_at(set, &evaluations, z),
+            ]);
+        }
+        let w_prime = self.open(l, z)?;
+
+        Ok(KzgMultiOpening(w.0, w_prime.0))
+    }
+
+    /// Verifies an opening produced by `multi_open`.
+    ///
+    /// # Parameters
+    ///
+    /// - `commitments`: The commitments to the opened polynomials.
+    /// - `points`: For each polynomial, the points at which it was opened.
+    /// - `evaluations`: For each polynomial, its claimed evaluations at `points`.
+    /// - `gamma`: The challenge used to combine the polynomials.
+    /// - `z`: The evaluation challenge derived from the first witness.
+    /// - `opening`: The multi-point opening to be verified.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the opening is valid, otherwise an error.
+    pub fn multi_verify(
+        &self,
+        commitments: &[KzgCommitment<E>],
+        points: &[Vec<Fr<E>>],
+        evaluations: &[Vec<Fr<E>>],
+        gamma: Fr<E>,
+        z: Fr<E>,
+        opening: &KzgMultiOpening<E>,
+    ) -> Result<(), KzgError> {
+        if commitments.len() != points.len()
+            || commitments.len() != evaluations.len()
+            || points
+                .iter()
+                .zip(evaluations)
+                .any(|(set, evaluation)| set.len() != evaluation.len())
+        {
+            return Err(KzgError::LengthMismatch);
+        }
+
+        let all_points = union(points);
+        let powers = powers_of(gamma, commitments.len());
+
+        // [L] = sum(gamma^i * Z_{T \ S_i}(z) * (C_i - [r_i(z)])) - Z_T(z) * W
+        let mut bases = Vec::with_capacity(commitments.len() + 3);
+        let mut scalars = Vec::with_capacity(commitments.len() + 3);
+        let mut constant = Fr::<E>::zero();
+        for (((commitment, set), evaluation), power) in
+            commitments.iter().zip(points).zip(evaluations).zip(&powers)
+        {
+            let factor = *power * evaluate_vanishing(&difference(&all_points, set), z);
+            bases.push(commitment.0);
+            scalars.push(factor);
+            constant += factor * interpolate_at(set, evaluation, z);
+        }
+        bases.push(self.g1());
+        scalars.push(-constant);
+        bases.push(opening.0);
+        scalars.push(-evaluate_vanishing(&all_points, z));
+
+        // e([L] + z * W', [1]) == e(W', [s])
+        bases.push(opening.1);
+        scalars.push(z);
+        let left = msm(&bases, &scalars);
+
+        let pairing = E::multi_pairing(
+            [left, -opening.1.into_group()],
+            [self.g2().into_group(), self.g2s().into_group()],
+        );
+        if !pairing.is_zero() {
+            return Err(KzgError::InvalidOpening);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the distinct points of all sets, in order of first appearance.
+fn union<F: PrimeField>(points: &[Vec<F>]) -> Vec<F> {
+    let mut result = Vec::new();
+    for point in points.iter().flatten() {
+        if !result.contains(point) {
+            result.push(*point);
+        }
+    }
+    result
+}
+
+/// Returns the points of `all_points` that are not in `set`.
+fn difference<F: PrimeField>(all_points: &[F], set: &[F]) -> Vec<F> {
+    all_points
+        .iter()
+        .filter(|point| !set.contains(point))
+        .copied()
+        .collect()
+}
+
+/// Returns the polynomial `prod(X - x)` over the given points.
+fn vanishing_poly<F: PrimeField>(points: &[F]) -> DensePolynomial<F> {
+    points
+        .iter()
+        .map(|point| DensePolynomial::from_coefficients_vec(vec![-*point, F::one()]))
+        .fold(
+            DensePolynomial::from_coefficients_vec(vec![F::one()]),
+            |acc, e| acc.naive_mul(&e),
+        )
+}
+
+/// Evaluates `prod(z - x)` over the given points.
+fn evaluate_vanishing<F: PrimeField>(points: &[F], z: F) -> F {
+    points.iter().map(|point| z - point).product()
+}
+
+/// Evaluates at `z` the polynomial interpolating `evaluations` over `points`.
+fn interpolate_at<F: PrimeField>(points: &[F], evaluations: &[F], z: F) -> F {
+    points
+        .iter()
+        .zip(evaluations)
+        .enumerate()
+        .map(|(j, (x_j, y_j))| {
+            let (numerator, denominator) = points
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| *k != j)
+                .fold((F::one(), F::one()), |(num, den), (_, x_k)| {
+                    (num * (z - x_k), den * (*x_j - x_k))
+                });
+            *y_j * numerator * denominator.inverse().expect("points must be distinct")
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{One, UniformRand};
+
+    use crate::srs::Srs;
+
+    use super::*;
+
+    fn setup() -> (KzgScheme, Vec<Poly>, Vec<Vec<Fr>>) {
+        let rng = &mut rand::thread_rng();
+        let scheme = KzgScheme::new(Srs::<Bls12_381>::new(16));
+        let polys = (0..4)
+            .map(|i| Poly::<Bls12_381>::rand(8 + i, rng))
+            .collect::<Vec<_>>();
+        let x = Fr::rand(rng);
+        let y = Fr::rand(rng);
+        let points = vec![vec![x], vec![x, y], vec![y], vec![x]];
+        (scheme, polys, points)
+    }
+
+    #[test]
+    fn multi_open_verify() {
+        let (scheme, polys, points) = setup();
+        let commitments = polys
+            .iter()
+            .map(|p| scheme.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let evaluations = polys
+            .iter()
+            .zip(&points)
+            .map(|(p, set)| set.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let gamma = Fr::from(7);
+        let z = Fr::from(1234);
+
+        let opening = scheme.multi_open(&polys, &points, gamma, |_| z).unwrap();
+        assert!(scheme
+            .multi_verify(&commitments, &points, &evaluations, gamma, z, &opening)
+            .is_ok());
+
+        // A wrong evaluation challenge is rejected.
+        assert_eq!(
+            scheme.multi_verify(
+                &commitments,
+                &points,
+                &evaluations,
+                gamma,
+                z + Fr::one(),
+                &opening
+            ),
+            Err(KzgError::InvalidOpening)
+        );
+
+        // A wrong evaluation is rejected.
+        let mut wrong = evaluations.clone();
+        wrong[1][1] += Fr::one();
+        assert_eq!(
+            scheme.multi_verify(&commitments, &points, &wrong, gamma, z, &opening),
+            Err(KzgError::InvalidOpening)
+        );
+
+        // A polynomial without a set of points is rejected.
+        assert_eq!(
+            scheme.multi_open(&polys, &points[1..], gamma, |_| z).err(),
+            Some(KzgError::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn interpolation() {
+        let (_, polys, points) = setup();
+        let poly = &polys[0];
+        let set = vec![Fr::from(1), Fr::from(2), Fr::from(3)];
+        let evaluations = set.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>();
+        let remainder = &(poly - &vanishing_poly(&set).naive_mul(&(poly / &vanishing_poly(&set))));
+        let z = points[0][0];
+        assert_eq!(
+            interpolate_at(&set, &evaluations, z),
+            remainder.evaluate(&z)
+        );
+    }
+}
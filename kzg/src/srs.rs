@@ -1,29 +1,89 @@
 use std::ops::Mul;
 
-use ark_bls12_381::Fr;
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{One, UniformRand};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
+use blake2::{Blake2b512, Digest};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use zeroize::Zeroize;
 
-use crate::types::{G1Point, G2Point};
+use crate::error::KzgError;
+use crate::msm::msm;
+use crate::opening::powers_of;
+use crate::types::{Fr, G1Point, G2Point};
+
+/// Degrees above the circuit size of the polynomials a PLONK prover commits to.
+///
+/// The permutation polynomial and the quotient slices are blinded up to degree `n + 2`
+/// for a circuit of size `n`, so an SRS for circuits of size `n` holds the powers up to
+/// `n + BLINDING_HEADROOM`.
+pub const BLINDING_HEADROOM: usize = 2;
+
+/// Returns the maximum degree of the committed polynomials of a circuit of a given size.
+///
+/// # Parameters
+///
+/// - `circuit_size`: The size of the circuit.
+///
+/// # Returns
+///
+/// `circuit_size + BLINDING_HEADROOM`, the degree an SRS for the circuit must support.
+pub const fn max_degree(circuit_size: usize) -> usize {
+    circuit_size + BLINDING_HEADROOM
+}
 
 /// Structured Reference String (SRS) used in the KZG scheme.
 ///
 /// The `Srs` struct represents the structured reference string used in the KZG scheme,
 /// containing precomputed values necessary for commitment and verification.
-#[derive(Debug, Clone, CanonicalDeserialize, CanonicalSerialize)]
-pub struct Srs {
+///
+/// Deserializing with validation runs `Srs::validate`, so an SRS loaded that way is
+/// well-formed.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize)]
+pub struct Srs<E: Pairing = Bls12_381> {
     /// Points in G1, each equals to generator point multiplied by the secret's powers.
-    g1_points: Vec<G1Point>,
+    g1_points: Vec<G1Point<E>>,
     /// Generator point in G2.
-    g2: G2Point,
+    g2: G2Point<E>,
     /// Generator point in G2 multiplied by the secret.
-    g2s_point: G2Point,
+    g2s_point: G2Point<E>,
+}
+
+impl<E: Pairing> Valid for Srs<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.validate().map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Srs<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let srs = Self {
+            g1_points: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            g2: G2Point::<E>::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            g2s_point: G2Point::<E>::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+        };
+        if let Validate::Yes = validate {
+            srs.check()?;
+        }
+        Ok(srs)
+    }
 }
 
-impl Srs {
+impl<E: Pairing> Srs<E> {
     /// Generates a new SRS with a random secret and the specified circuit size.
     ///
+    /// The secret is erased from memory once the SRS is generated.
+    ///
     /// # Parameters
     ///
     /// - `circuit_size`: The size of the circuit.
@@ -32,52 +92,403 @@ impl Srs {
     ///
     /// A new `Srs` instance.
     pub fn new(circuit_size: usize) -> Self {
-        let s = Fr::rand(&mut rand::thread_rng());
-        Self::new_from_secret(s, circuit_size)
+        let mut s = Fr::<E>::rand(&mut rand::thread_rng());
+        Self::from_secret(&mut s, circuit_size)
     }
 
     /// Generates a new SRS with the provided secret and the specified circuit size.
     ///
+    /// Anyone knowing the secret can forge proofs, so this is only available for tests
+    /// and with the `dev-setup` feature. The secret is erased from the caller's memory once
+    /// the SRS is generated.
+    ///
+    /// # Parameters
+    ///
+    /// - `secret`: The secret used for generating the SRS, zeroized on return.
+    /// - `circuit_size`: The size of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// A new `Srs` instance.
+    #[cfg(any(test, feature = "dev-setup"))]
+    pub fn new_from_secret(secret: &mut Fr<E>, circuit_size: usize) -> Self {
+        Self::from_secret(secret, circuit_size)
+    }
+
+    /// Generates an SRS deterministically from a seed, for reproducible tests.
+    ///
+    /// The secret is derived from the seed with ChaCha20, so anyone knowing the seed
+    /// can forge proofs. Never use such an SRS in production.
+    ///
     /// # Parameters
     ///
-    /// - `secret`: The secret used for generating the SRS.
+    /// - `seed`: The seed the secret is derived from.
     /// - `circuit_size`: The size of the circuit.
     ///
     /// # Returns
     ///
     /// A new `Srs` instance.
-    pub fn new_from_secret(secret: Fr, circuit_size: usize) -> Self {
-        let g1 = G1Point::generator();
+    pub fn insecure_from_seed(seed: u64, circuit_size: usize) -> Self {
+        let mut s = Fr::<E>::rand(&mut ChaCha20Rng::seed_from_u64(seed));
+        Self::from_secret(&mut s, circuit_size)
+    }
+
+    /// Generates an SRS from a secret, and erases the secret and its powers from memory.
+    fn from_secret(secret: &mut Fr<E>, circuit_size: usize) -> Self {
+        let g1 = G1Point::<E>::generator();
 
-        let g1_points = vec![Fr::one(); circuit_size + 3];
-        let mut cur = Fr::one();
+        let g1_points = vec![Fr::<E>::one(); max_degree(circuit_size) + 1];
+        let mut cur = Fr::<E>::one();
         let g1_points = g1_points
             .into_iter()
             .map(|_| {
                 let res = g1.mul(cur).into_affine();
-                cur *= secret;
+                cur *= *secret;
                 res
             })
             .collect::<Vec<_>>();
 
-        let g2 = G2Point::generator();
-        let g2s_point = g2.mul(secret).into();
+        let g2 = G2Point::<E>::generator();
+        let g2s_point = g2.mul(*secret).into_affine();
+        cur.zeroize();
+        secret.zeroize();
+        Self {
+            g1_points,
+            g2,
+            g2s_point,
+        }
+    }
+
+    /// Generates the starting SRS of a ceremony, in which the secret equals one.
+    ///
+    /// Every point is a generator, so this SRS is only safe to use after at least one
+    /// honest party has called `contribute` on it.
+    ///
+    /// # Parameters
+    ///
+    /// - `circuit_size`: The size of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// A new `Srs` instance.
+    pub fn genesis(circuit_size: usize) -> Self {
+        Self::from_secret(&mut Fr::<E>::one(), circuit_size)
+    }
+}
+
+/// Proof that an SRS was obtained by a well-formed contribution to a previous one.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalDeserialize, CanonicalSerialize)]
+pub struct ContributionProof<E: Pairing = Bls12_381> {
+    /// Generator point in G2 multiplied by the contributed secret.
+    pub update: G2Point<E>,
+    /// Generator point in G1 multiplied by the contributed secret.
+    pub public_key: G1Point<E>,
+    /// Commitment `[k]_1` of the Schnorr proof of knowledge of the secret.
+    pub commitment: G1Point<E>,
+    /// Response `k + c * r` of the Schnorr proof of knowledge of the secret.
+    pub response: Fr<E>,
+}
+
+impl<E: Pairing> ContributionProof<E> {
+    /// Derives the Schnorr challenge, binding the proof to the SRS it was made on top of.
+    fn challenge(prev: &Srs<E>, public_key: &G1Point<E>, commitment: &G1Point<E>) -> Fr<E> {
+        let mut bytes = Vec::new();
+        for point in [&prev.g1_points[1], public_key, commitment] {
+            point
+                .serialize_compressed(&mut bytes)
+                .expect("serializing to a vector cannot fail");
+        }
+        prev.g2s_point
+            .serialize_compressed(&mut bytes)
+            .expect("serializing to a vector cannot fail");
+        Fr::<E>::from_le_bytes_mod_order(&Blake2b512::digest(&bytes))
+    }
+}
+
+impl<E: Pairing> Srs<E> {
+    /// Contributes a fresh secret to the SRS.
+    ///
+    /// Every power of the current secret `s` is re-randomised, so that the SRS then
+    /// encodes the powers of `s * r` for a random `r`. The secret `r` and its powers are
+    /// erased from memory once the contribution is done, so the resulting SRS is secure as long as a single
+    /// contributor has been honest.
+    ///
+    /// # Parameters
+    ///
+    /// - `rng`: The source of randomness for the contributed secret.
+    ///
+    /// # Returns
+    ///
+    /// The proof that the contribution is well-formed.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> ContributionProof<E> {
+        let mut r = Fr::<E>::rand(rng);
+        while r.is_zero() {
+            r = Fr::<E>::rand(rng);
+        }
+
+        // Schnorr proof of knowledge of r, bound to the SRS before the contribution.
+        let g1 = self.g1_points[0];
+        let mut k = Fr::<E>::rand(rng);
+        let public_key = g1.mul(r).into_affine();
+        let commitment = g1.mul(k).into_affine();
+        let challenge = ContributionProof::challenge(self, &public_key, &commitment);
+        let response = k + challenge * r;
+        k.zeroize();
+
+        let mut powers = powers_of(r, self.g1_points.len());
+        let updated = self
+            .g1_points
+            .iter()
+            .zip(&powers)
+            .map(|(point, power)| point.mul(power))
+            .collect::<Vec<_>>();
+        self.g1_points = E::G1::normalize_batch(&updated);
+        self.g2s_point = self.g2s_point.mul(r).into_affine();
+        let update = self.g2.mul(r).into_affine();
+        powers.zeroize();
+        r.zeroize();
+
+        ContributionProof {
+            update,
+            public_key,
+            commitment,
+            response,
+        }
+    }
+
+    /// Verifies that `next` was obtained from `prev` by a well-formed contribution.
+    ///
+    /// The following is checked:
+    /// - the contributor knows the secret in `proof`, through its Schnorr proof,
+    /// - the secret in `proof` is the same in G1 and G2,
+    /// - the secret of `next` is the secret of `prev` multiplied by the secret in `proof`,
+    /// - the G2 point of `next` matches its G1 points,
+    /// - the G1 points of `next` are consecutive powers of a single secret, using a
+    ///   random linear combination of all of them.
+    ///
+    /// # Parameters
+    ///
+    /// - `prev`: The SRS before the contribution.
+    /// - `next`: The SRS after the contribution.
+    /// - `proof`: The proof of the contribution.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the contribution is valid, otherwise `false`.
+    pub fn verify_contribution(prev: &Srs<E>, next: &Srs<E>, proof: &ContributionProof<E>) -> bool {
+        if prev.g1_points.len() != next.g1_points.len()
+            || prev.g1_points.len() < 2
+            || !prev.is_well_formed()
+            || !next.is_well_formed()
+            || proof.update.is_zero()
+            || proof.public_key.is_zero()
+        {
+            return false;
+        }
+
+        // [z]_1 == [k]_1 + c * [r]_1
+        let g1 = prev.g1_points[0];
+        let challenge = ContributionProof::challenge(prev, &proof.public_key, &proof.commitment);
+        if g1.mul(proof.response) != proof.commitment + proof.public_key.mul(challenge) {
+            return false;
+        }
+
+        // e([r]_1, [1]_2) == e([1]_1, [r]_2)
+        let update = E::multi_pairing(
+            [proof.public_key.into_group(), -g1.into_group()],
+            [prev.g2, proof.update],
+        );
+        // e([s * r]_1, [1]_2) == e([s]_1, [r]_2)
+        let powers = E::multi_pairing(
+            [
+                next.g1_points[1].into_group(),
+                -prev.g1_points[1].into_group(),
+            ],
+            [next.g2, proof.update],
+        );
+        update.is_zero() && powers.is_zero()
+    }
+
+    /// Creates an SRS from existing points, checking that it is well-formed.
+    ///
+    /// # Parameters
+    ///
+    /// - `g1_points`: The powers of the secret in G1, starting from the generator.
+    /// - `g2`: The generator point in G2.
+    /// - `g2s_point`: The generator point in G2 multiplied by the secret.
+    ///
+    /// # Returns
+    ///
+    /// A new `Srs` instance, or an error if the points are not consistent.
+    pub(crate) fn from_points(
+        g1_points: Vec<G1Point<E>>,
+        g2: G2Point<E>,
+        g2s_point: G2Point<E>,
+    ) -> Result<Self, String> {
+        let srs = Self {
+            g1_points,
+            g2,
+            g2s_point,
+        };
+        if !srs.is_well_formed() {
+            return Err("SRS points are not consecutive powers of a secret".to_string());
+        }
+        Ok(srs)
+    }
+
+    /// Creates an SRS from existing points, without checking them.
+    pub(crate) fn from_points_unchecked(
+        g1_points: Vec<G1Point<E>>,
+        g2: G2Point<E>,
+        g2s_point: G2Point<E>,
+    ) -> Self {
         Self {
             g1_points,
             g2,
             g2s_point,
         }
     }
+
+    /// Checks that the SRS is well-formed.
+    ///
+    /// Every point must be on the curve and in the prime order subgroup, and the G1
+    /// points must be consecutive powers of the secret encoded in the G2 point. This
+    /// should be called on any SRS loaded without validation.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the SRS is valid, otherwise an error describing the problem.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(i) = self
+            .g1_points
+            .iter()
+            .position(|point| point.check().is_err())
+        {
+            return Err(format!(
+                "SRS: G1 point {} is not in the prime order subgroup",
+                i
+            ));
+        }
+        if self.g2.check().is_err() || self.g2s_point.check().is_err() {
+            return Err("SRS: G2 point is not in the prime order subgroup".to_string());
+        }
+        if !self.is_well_formed() {
+            return Err("SRS: points are not consecutive powers of a secret".to_string());
+        }
+        Ok(())
+    }
+
+    /// Checks with pairings that the SRS encodes consecutive powers of a single secret.
+    ///
+    /// The G1 points are checked together through a random linear combination. A
+    /// verifier subset, which has a single G1 point, only has its generators checked.
+    fn is_well_formed(&self) -> bool {
+        let g1 = G1Point::<E>::generator();
+        let g2 = G2Point::<E>::generator();
+        let len = self.g1_points.len();
+        if len == 0 || self.g2 != g2 || self.g1_points[0] != g1 || self.g2s_point.is_zero() {
+            return false;
+        }
+        if len == 1 {
+            return true;
+        }
+        if self.g1_points[1].is_zero() {
+            return false;
+        }
+
+        // e([1]_1, [s]_2) == e([s]_1, [1]_2)
+        if E::pairing(g1, self.g2s_point) != E::pairing(self.g1_points[1], g2) {
+            return false;
+        }
+
+        // e(sum(rho^i * [s^(i+1)]_1), [1]_2) == e(sum(rho^i * [s^i]_1), [s]_2)
+        let rho = Fr::<E>::rand(&mut rand::thread_rng());
+        let powers = powers_of(rho, len - 1);
+        let shifted = msm(&self.g1_points[1..], &powers).into_affine();
+        let unshifted = msm(&self.g1_points[..len - 1], &powers).into_affine();
+        E::pairing(shifted, g2) == E::pairing(unshifted, self.g2s_point)
+    }
 }
 
-impl Srs {
+impl<E: Pairing> Srs<E> {
+    /// Truncates the SRS to the powers needed to commit to polynomials of a given degree.
+    ///
+    /// The secret is unchanged, so the truncated SRS is compatible with the original one.
+    ///
+    /// # Parameters
+    ///
+    /// - `max_degree`: The maximum degree of the committed polynomials.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the SRS was truncated, or `KzgError::DegreeExceedsSrs` if it does not
+    /// support polynomials of degree `max_degree`.
+    pub fn truncate(&mut self, max_degree: usize) -> Result<(), KzgError> {
+        if max_degree >= self.g1_points.len() {
+            return Err(KzgError::DegreeExceedsSrs {
+                degree: max_degree,
+                max: self.g1_points.len().saturating_sub(1),
+            });
+        }
+        self.g1_points.truncate(max_degree + 1);
+        Ok(())
+    }
+
+    /// Extracts the points needed by a verifier that is given the circuit commitments.
+    ///
+    /// # Returns
+    ///
+    /// An SRS holding only the first G1 point, the generator in G2 and the secret in G2.
+    pub fn verifier_subset(&self) -> Self {
+        Self {
+            g1_points: self.g1_points[..1].to_vec(),
+            g2: self.g2,
+            g2s_point: self.g2s_point,
+        }
+    }
+
+    /// Computes the commitments to the Lagrange basis polynomials of a domain.
+    ///
+    /// The `i`-th point equals the generator multiplied by `L_i(s)`, where `L_i` is the
+    /// Lagrange polynomial of the `i`-th element of the domain. It is obtained with an
+    /// inverse FFT over the first `domain.size()` points in G1.
+    ///
+    /// # Parameters
+    ///
+    /// - `domain`: The evaluation domain of the basis.
+    ///
+    /// # Returns
+    ///
+    /// A vector containing one point in G1 per element of the domain, or
+    /// `KzgError::DegreeExceedsSrs` if the SRS has fewer points than the domain.
+    pub fn to_lagrange_basis(
+        &self,
+        domain: GeneralEvaluationDomain<Fr<E>>,
+    ) -> Result<Vec<G1Point<E>>, KzgError> {
+        let size = domain.size();
+        if self.g1_points.len() < size {
+            return Err(KzgError::DegreeExceedsSrs {
+                degree: size - 1,
+                max: self.g1_points.len().saturating_sub(1),
+            });
+        }
+
+        let points = self.g1_points[..size]
+            .iter()
+            .map(|point| point.into_group())
+            .collect::<Vec<_>>();
+        Ok(E::G1::normalize_batch(&domain.ifft(&points)))
+    }
+}
+
+impl<E: Pairing> Srs<E> {
     /// Returns the precomputed points in G1.
     ///
     /// # Returns
     ///
-    /// A vector containing points in G1.
-    pub fn g1_points(&self) -> Vec<G1Point> {
-        self.g1_points.clone()
+    /// A slice containing points in G1.
+    pub fn g1_points(&self) -> &[G1Point<E>] {
+        &self.g1_points
     }
 
     /// Returns the generator point in G2.
@@ -85,7 +496,7 @@ impl Srs {
     /// # Returns
     ///
     /// The generator point in G2.
-    pub fn g2(&self) -> G2Point {
+    pub fn g2(&self) -> G2Point<E> {
         self.g2
     }
 
@@ -94,7 +505,317 @@ impl Srs {
     /// # Returns
     ///
     /// The generator point in G2 multiplied by the secret.
-    pub fn g2s(&self) -> G2Point {
+    pub fn g2s(&self) -> G2Point<E> {
         self.g2s_point
     }
 }
+
+/// Structured Reference String extended with the powers of the secret on a blinding generator.
+///
+/// Besides the points of a plain `Srs`, it holds `[s^i]H` for a second generator `H` of
+/// G1 whose discrete logarithm with respect to the generator is unknown. These points
+/// are used for hiding commitments.
+#[derive(Debug, Clone, CanonicalDeserialize, CanonicalSerialize)]
+pub struct HidingSrs<E: Pairing = Bls12_381> {
+    /// The plain SRS.
+    srs: Srs<E>,
+    /// Points in G1, each equals to the blinding generator multiplied by the secret's powers.
+    h_points: Vec<G1Point<E>>,
+}
+
+impl<E: Pairing> HidingSrs<E> {
+    /// Generates a new hiding SRS with a random secret and blinding generator.
+    ///
+    /// # Parameters
+    ///
+    /// - `circuit_size`: The size of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// A new `HidingSrs` instance.
+    pub fn new(circuit_size: usize) -> Self {
+        let rng = &mut rand::thread_rng();
+        let mut s = Fr::<E>::rand(rng);
+        let mut h = Fr::<E>::rand(rng);
+        while h.is_zero() {
+            h = Fr::<E>::rand(rng);
+        }
+        Self::from_secret(&mut s, &mut h, circuit_size)
+    }
+
+    /// Generates a new hiding SRS with the provided secrets and the specified circuit size.
+    ///
+    /// Like `Srs::new_from_secret`, this is only available for tests and with the
+    /// `dev-setup` feature, and both secrets are erased from the caller's memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `secret`: The secret used for generating the SRS, zeroized on return.
+    /// - `blinding_secret`: The discrete logarithm of the blinding generator, zeroized on
+    ///   return.
+    /// - `circuit_size`: The size of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// A new `HidingSrs` instance.
+    #[cfg(any(test, feature = "dev-setup"))]
+    pub fn new_from_secret(
+        secret: &mut Fr<E>,
+        blinding_secret: &mut Fr<E>,
+        circuit_size: usize,
+    ) -> Self {
+        Self::from_secret(secret, blinding_secret, circuit_size)
+    }
+
+    /// Generates a hiding SRS from its secrets, and erases them from memory.
+    fn from_secret(secret: &mut Fr<E>, blinding_secret: &mut Fr<E>, circuit_size: usize) -> Self {
+        let srs = Srs::from_secret(secret, circuit_size);
+        let h_points = srs
+            .g1_points
+            .iter()
+            .map(|point: &G1Point<E>| point.mul(*blinding_secret))
+            .collect::<Vec<_>>();
+        let h_points = E::G1::normalize_batch(&h_points);
+        blinding_secret.zeroize();
+        Self { srs, h_points }
+    }
+
+    /// Checks that the hiding SRS is well-formed.
+    ///
+    /// On top of the checks of `Srs::validate`, the blinding points must be in the prime
+    /// order subgroup, as many as the G1 points, and consecutive powers of the same secret.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the hiding SRS is valid, otherwise an error describing the problem.
+    pub fn validate(&self) -> Result<(), String> {
+        self.srs.validate()?;
+        if self.h_points.len() != self.srs.g1_points.len() {
+            return Err("SRS: numbers of G1 and blinding points do not match".to_string());
+        }
+        if let Some(i) = self
+            .h_points
+            .iter()
+            .position(|point| point.check().is_err())
+        {
+            return Err(format!(
+                "SRS: blinding point {} is not in the prime order subgroup",
+                i
+            ));
+        }
+        if self.h_points[0].is_zero() || self.h_points[0] == self.srs.g1_points[0] {
+            return Err("SRS: blinding generator is degenerate".to_string());
+        }
+
+        // e(sum(rho^i * [s^(i+1)]H), [1]_2) == e(sum(rho^i * [s^i]H), [s]_2)
+        let len = self.h_points.len();
+        let rho = Fr::<E>::rand(&mut rand::thread_rng());
+        let powers = powers_of(rho, len - 1);
+        let shifted = msm(&self.h_points[1..], &powers).into_affine();
+        let unshifted = msm(&self.h_points[..len - 1], &powers).into_affine();
+        if E::pairing(shifted, self.srs.g2) != E::pairing(unshifted, self.srs.g2s_point) {
+            return Err(
+                "SRS: blinding points are not consecutive powers of the secret".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the plain SRS.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the plain `Srs`.
+    pub fn srs(&self) -> &Srs<E> {
+        &self.srs
+    }
+
+    /// Returns the points of the blinding generator multiplied by the secret's powers.
+    ///
+    /// # Returns
+    ///
+    /// A slice containing points in G1.
+    pub fn h_points(&self) -> &[G1Point<E>] {
+        &self.h_points
+    }
+
+    /// Splits the hiding SRS into the plain SRS and the blinding points.
+    ///
+    /// # Returns
+    ///
+    /// The plain `Srs` and the blinding points.
+    pub fn into_parts(self) -> (Srs<E>, Vec<G1Point<E>>) {
+        (self.srs, self.h_points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, Fr};
+
+    use super::*;
+
+    #[test]
+    fn contribution() {
+        let rng = &mut rand::thread_rng();
+        let genesis = Srs::<Bls12_381>::genesis(8);
+
+        let mut first = genesis.clone();
+        let first_proof = first.contribute(rng);
+        assert!(Srs::verify_contribution(&genesis, &first, &first_proof));
+
+        let mut second = first.clone();
+        let second_proof = second.contribute(rng);
+        assert!(Srs::verify_contribution(&first, &second, &second_proof));
+
+        // A proof does not verify against the wrong previous SRS.
+        assert!(!Srs::verify_contribution(&genesis, &second, &second_proof));
+        assert!(!Srs::verify_contribution(&first, &second, &first_proof));
+    }
+
+    #[test]
+    fn contribution_requires_proof_of_knowledge() {
+        let rng = &mut rand::thread_rng();
+        let genesis = Srs::<Bls12_381>::genesis(8);
+        let mut next = genesis.clone();
+        let proof = next.contribute(rng);
+
+        let mut tampered = proof.clone();
+        tampered.response += Fr::from(1);
+        assert!(!Srs::verify_contribution(&genesis, &next, &tampered));
+
+        // A public key for another secret is rejected, even with a valid Schnorr proof.
+        let mut other = genesis.clone();
+        let other_proof = other.contribute(rng);
+        let mut tampered = proof.clone();
+        tampered.public_key = other_proof.public_key;
+        tampered.commitment = other_proof.commitment;
+        tampered.response = other_proof.response;
+        assert!(!Srs::verify_contribution(&genesis, &next, &tampered));
+    }
+
+    #[test]
+    fn validate() {
+        let mut srs = Srs::<Bls12_381>::new(8);
+        assert!(srs.validate().is_ok());
+
+        // A point off the curve is rejected.
+        srs.g1_points[3].y += Fq::from(1);
+        assert!(srs.validate().is_err());
+
+        // Powers of two different secrets are rejected.
+        let mut srs = Srs::<Bls12_381>::new(8);
+        srs.g1_points[5] = Srs::<Bls12_381>::new(8).g1_points[5];
+        assert!(srs.validate().is_err());
+
+        let mut srs = Srs::<Bls12_381>::new(8);
+        srs.g2s_point = Srs::<Bls12_381>::new(8).g2s_point;
+        assert!(srs.validate().is_err());
+
+        // Deserializing with validation validates the SRS.
+        let mut bytes = Vec::new();
+        srs.serialize_compressed(&mut bytes).unwrap();
+        assert!(Srs::<Bls12_381>::deserialize_compressed(&bytes[..]).is_err());
+        assert!(Srs::<Bls12_381>::deserialize_compressed_unchecked(&bytes[..]).is_ok());
+    }
+
+    #[test]
+    fn lagrange_basis() {
+        let secret = Fr::from(1234);
+        let mut erased = secret;
+        let srs = Srs::<Bls12_381>::new_from_secret(&mut erased, 8);
+        assert!(erased.is_zero());
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+
+        let basis = srs.to_lagrange_basis(domain).unwrap();
+        let evaluations = domain.evaluate_all_lagrange_coefficients(secret);
+        assert_eq!(basis.len(), 8);
+        for (point, evaluation) in basis.iter().zip(evaluations) {
+            assert_eq!(
+                *point,
+                G1Point::<Bls12_381>::generator()
+                    .mul(evaluation)
+                    .into_affine()
+            );
+        }
+    }
+
+    #[test]
+    fn contribution_rejects_tampered_powers() {
+        let rng = &mut rand::thread_rng();
+        let genesis = Srs::<Bls12_381>::genesis(8);
+        let mut next = genesis.clone();
+        let proof = next.contribute(rng);
+
+        next.g1_points[4] = (next.g1_points[4] + G1Point::<Bls12_381>::generator()).into_affine();
+        assert!(!Srs::verify_contribution(&genesis, &next, &proof));
+    }
+
+    #[test]
+    fn truncate() {
+        let srs = Srs::<Bls12_381>::new(8);
+        // An SRS for circuits of size 8 is left untouched by trimming it to that size.
+        let mut untouched = srs.clone();
+        untouched.truncate(max_degree(8)).unwrap();
+        assert_eq!(untouched, srs);
+
+        let mut truncated = srs.clone();
+        truncated.truncate(5).unwrap();
+        assert_eq!(truncated.g1_points, srs.g1_points[..6]);
+        assert_eq!(truncated.g2s_point, srs.g2s_point);
+        assert!(truncated.validate().is_ok());
+
+        assert_eq!(
+            truncated.truncate(6),
+            Err(KzgError::DegreeExceedsSrs { degree: 6, max: 5 })
+        );
+    }
+
+    #[test]
+    fn verifier_subset() {
+        let srs = Srs::<Bls12_381>::new(8);
+        let subset = srs.verifier_subset();
+        assert_eq!(subset.g1_points, srs.g1_points[..1]);
+        assert_eq!(subset.g2, srs.g2);
+        assert_eq!(subset.g2s_point, srs.g2s_point);
+        assert!(subset.validate().is_ok());
+
+        let mut wrong = subset.clone();
+        wrong.g1_points[0] = srs.g1_points[1];
+        assert!(wrong.validate().is_err());
+    }
+
+    #[test]
+    fn insecure_from_seed() {
+        let srs = Srs::<Bls12_381>::insecure_from_seed(42, 8);
+        assert!(srs.validate().is_ok());
+        assert_eq!(
+            srs.g1_points,
+            Srs::<Bls12_381>::insecure_from_seed(42, 8).g1_points
+        );
+        assert_ne!(
+            srs.g1_points,
+            Srs::<Bls12_381>::insecure_from_seed(43, 8).g1_points
+        );
+    }
+
+    #[test]
+    fn validate_hiding() {
+        let srs = HidingSrs::<Bls12_381>::new(8);
+        assert!(srs.validate().is_ok());
+
+        // Blinding points of another secret are rejected.
+        let mut tampered = srs.clone();
+        tampered.h_points[5] = HidingSrs::<Bls12_381>::new(8).h_points[5];
+        assert!(tampered.validate().is_err());
+
+        // A blinding generator equal to the generator is rejected.
+        let degenerate =
+            HidingSrs::<Bls12_381>::new_from_secret(&mut Fr::from(3), &mut Fr::one(), 8);
+        assert!(degenerate.validate().is_err());
+
+        let mut truncated = srs;
+        truncated.h_points.pop();
+        assert!(truncated.validate().is_err());
+    }
+}
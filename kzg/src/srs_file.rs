@@ -0,0 +1,215 @@
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::{Blake2b512, Digest};
+
+use crate::srs::Srs;
+use crate::types::G1Point;
+
+/// Magic bytes at the start of a versioned SRS file.
+pub const SRS_MAGIC: [u8; 4] = *b"KSRS";
+
+/// Version of the SRS file format written by `Srs::to_bytes`.
+pub const SRS_FORMAT_VERSION: u8 = 1;
+
+/// Size in bytes of the header: magic, version, curve id and number of powers.
+pub(crate) const HEADER_BYTES: usize = 4 + 1 + 1 + 8;
+
+/// Size in bytes of the blake2b checksum at the end of a versioned SRS file.
+pub(crate) const CHECKSUM_BYTES: usize = 64;
+
+/// Pairing-friendly curves that have an identifier in the SRS file format.
+pub trait SrsCurve: Pairing {
+    /// The identifier of the curve in SRS files.
+    const CURVE_ID: u8;
+    /// The human-readable name of the curve.
+    const NAME: &'static str;
+}
+
+impl SrsCurve for Bls12_381 {
+    const CURVE_ID: u8 = 1;
+    const NAME: &'static str = "BLS12-381";
+}
+
+impl SrsCurve for ark_bn254::Bn254 {
+    const CURVE_ID: u8 = 2;
+    const NAME: &'static str = "BN254";
+}
+
+impl SrsCurve for ark_bls12_377::Bls12_377 {
+    const CURVE_ID: u8 = 3;
+    const NAME: &'static str = "BLS12-377";
+}
+
+impl<E: SrsCurve> Srs<E> {
+    /// Serializes the SRS into the versioned SRS file format.
+    ///
+    /// The file holds the magic bytes, the format version, the curve id, the number of
+    /// G1 powers as a little-endian `u64`, the compressed points and a blake2b-512
+    /// checksum of everything before it.
+    ///
+    /// # Returns
+    ///
+    /// The bytes of the SRS file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_BYTES + self.compressed_size() + CHECKSUM_BYTES);
+        bytes.extend_from_slice(&SRS_MAGIC);
+        bytes.push(SRS_FORMAT_VERSION);
+        bytes.push(E::CURVE_ID);
+        bytes.extend_from_slice(&(self.g1_points().len() as u64).to_le_bytes());
+        for point in self.g1_points() {
+            point
+                .serialize_compressed(&mut bytes)
+                .expect("Serializing into a vector cannot fail");
+        }
+        self.g2()
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a vector cannot fail");
+        self.g2s()
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a vector cannot fail");
+        let checksum = Blake2b512::digest(&bytes);
+        bytes.extend_from_slice(&checksum);
+        bytes
+    }
+
+    /// Reads an SRS file, either in the versioned format or as a raw uncompressed blob.
+    ///
+    /// Files starting with `SRS_MAGIC` are read in the versioned format, whose checksum,
+    /// version, curve and length are checked. Any other file is read in the raw format
+    /// written by `serialize_uncompressed`. In both cases the SRS is validated.
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The content of the SRS file.
+    ///
+    /// # Returns
+    ///
+    /// The SRS, or an error describing why the file is invalid.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.starts_with(&SRS_MAGIC) {
+            return Self::from_versioned_bytes(bytes);
+        }
+        let srs = Self::deserialize_uncompressed_unchecked(bytes)
+            .map_err(|e| format!("SRS: invalid raw SRS file: {}", e))?;
+        srs.validate()?;
+        Ok(srs)
+    }
+
+    fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let (content, count) = read_versioned_header::<E>(bytes)?;
+
+        let mut reader = &content[HEADER_BYTES..];
+        let g1_points = (0..count)
+            .map(|_| CanonicalDeserialize::deserialize_compressed(&mut reader))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("SRS: invalid G1 point: {}", e))?;
+        let g2 = CanonicalDeserialize::deserialize_compressed(&mut reader)
+            .map_err(|e| format!("SRS: invalid G2 point: {}", e))?;
+        let g2s = CanonicalDeserialize::deserialize_compressed(&mut reader)
+            .map_err(|e| format!("SRS: invalid G2 point: {}", e))?;
+        if !reader.is_empty() {
+            return Err("SRS: unexpected trailing bytes".to_string());
+        }
+        Self::from_points(g1_points, g2, g2s)
+    }
+
+    /// Returns the size in bytes of the compressed points.
+    fn compressed_size(&self) -> usize {
+        self.g1_points().len() * G1Point::<E>::generator().compressed_size()
+            + 2 * self.g2().compressed_size()
+    }
+}
+
+/// Checks the header and the checksum of a versioned SRS file.
+///
+/// # Parameters
+///
+/// - `bytes`: The content of the SRS file, starting with `SRS_MAGIC`.
+///
+/// # Returns
+///
+/// The content of the file without its checksum, and the number of G1 powers in the
+/// header, or an error if the file is truncated, corrupted or for another curve.
+pub(crate) fn read_versioned_header<E: SrsCurve>(bytes: &[u8]) -> Result<(&[u8], u64), String> {
+    if bytes.len() < HEADER_BYTES + CHECKSUM_BYTES {
+        return Err("SRS: file is truncated".to_string());
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_BYTES);
+    if Blake2b512::digest(content).as_slice() != checksum {
+        return Err("SRS: checksum mismatch, the file is corrupted or truncated".to_string());
+    }
+
+    let version = content[4];
+    if version != SRS_FORMAT_VERSION {
+        return Err(format!("SRS: unsupported file format version {}", version));
+    }
+    let curve_id = content[5];
+    if curve_id != E::CURVE_ID {
+        return Err(format!(
+            "SRS: file is for curve id {}, expected {} ({})",
+            curve_id,
+            E::CURVE_ID,
+            E::NAME
+        ));
+    }
+    let count = u64::from_le_bytes(content[6..HEADER_BYTES].try_into().expect("8 bytes"));
+    Ok((content, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+
+    use super::*;
+
+    #[test]
+    fn versioned_roundtrip() {
+        let srs = Srs::<Bls12_381>::new(8);
+        let bytes = srs.to_bytes();
+        assert!(bytes.starts_with(&SRS_MAGIC));
+
+        let read = Srs::<Bls12_381>::from_bytes(&bytes).unwrap();
+        assert_eq!(read.g1_points(), srs.g1_points());
+        assert_eq!(read.g2s(), srs.g2s());
+
+        // Compressed points take half the space of the raw format.
+        let mut raw = Vec::new();
+        srs.serialize_uncompressed(&mut raw).unwrap();
+        assert!(bytes.len() < raw.len() / 2 + HEADER_BYTES + CHECKSUM_BYTES);
+    }
+
+    #[test]
+    fn raw_format() {
+        let srs = Srs::<Bls12_381>::new(8);
+        let mut raw = Vec::new();
+        srs.serialize_uncompressed(&mut raw).unwrap();
+        let read = Srs::<Bls12_381>::from_bytes(&raw).unwrap();
+        assert_eq!(read.g1_points(), srs.g1_points());
+
+        raw.truncate(raw.len() - 1);
+        assert!(Srs::<Bls12_381>::from_bytes(&raw).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let bytes = Srs::<Bls12_381>::new(8).to_bytes();
+
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_BYTES + 3] ^= 1;
+        assert!(Srs::<Bls12_381>::from_bytes(&corrupted)
+            .unwrap_err()
+            .contains("checksum"));
+
+        assert!(Srs::<Bls12_381>::from_bytes(&bytes[..bytes.len() - 10]).is_err());
+        assert!(Srs::<Bls12_381>::from_bytes(&bytes[..HEADER_BYTES]).is_err());
+
+        // The curve id is checked.
+        let bn254 = Srs::<Bn254>::new(8).to_bytes();
+        assert!(Srs::<Bls12_381>::from_bytes(&bn254)
+            .unwrap_err()
+            .contains("curve"));
+        assert!(Srs::<Bn254>::from_bytes(&bn254).is_ok());
+    }
+}
@@ -51,15 +51,18 @@ fn main() {
         (0, 6, Fr::from(3)),
         (1, 6, Fr::from(0)),
         (1, 2, Fr::from(3)),
+        Fr::from(3),
         Fr::from(0),
     );
 
     let compiled_circuit = circuit.compile().unwrap();
 
     // generate proof
-    let srs = Srs::new(compiled_circuit.size);
-    let proof = prover::generate_proof::<Sha256>(&compiled_circuit, srs.clone());
+    let srs: Srs = Srs::new(compiled_circuit.size);
+    let proof =
+        prover::generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs.clone())
+            .unwrap();
 
     // verify proof
-    assert!(verifier::verify::<Sha256>(&compiled_circuit, srs, proof).is_ok());
+    assert!(verifier::verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_ok());
 }
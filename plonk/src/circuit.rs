@@ -1,38 +1,29 @@
 use std::collections::HashMap;
-use std::sync::Arc;
 
 use ark_bls12_381::Fr;
-use ark_ff::One;
+use ark_ff::PrimeField;
 use ark_poly::{EvaluationDomain, Evaluations, GeneralEvaluationDomain};
+use ark_std::cfg_into_iter;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::compiled_circuit::CompiledCircuit;
 use crate::constraint::{CopyConstraints, GateConstraints};
 use crate::gate::{Gate, Position};
+use crate::witness::Witness;
 
 /// Represents a circuit consisting of gates and values.
-#[derive(PartialEq, Debug)]
-pub struct Circuit {
-    gates: Vec<Gate>,
-    vals: Vec<Arc<Vec<Fr>>>,
+///
+/// The gates make up the structure of the circuit, which is compiled once, and the
+/// values make up the witness of a proof.
+#[derive(PartialEq, Debug, Default)]
+pub struct Circuit<F: PrimeField = Fr> {
+    gates: Vec<Gate<F>>,
+    witness: Witness<F>,
+    public_input_rows: Vec<usize>,
 }
 
-impl Default for Circuit {
-    fn default() -> Self {
-        Self {
-            gates: Vec::default(),
-            vals: vec![
-                Arc::new(Vec::default()),
-                Arc::new(Vec::default()),
-                Arc::new(Vec::default()),
-            ],
-        }
-    }
-}
-
-impl Circuit {
-    pub const VEC_A: &'static str = "vec_a";
-    pub const VEC_B: &'static str = "vec_b";
-    pub const VEC_C: &'static str = "vec_c";
+impl<F: PrimeField> Circuit<F> {
     pub const VEC_QL: &'static str = "vec_ql";
     pub const VEC_QR: &'static str = "vec_qr";
     pub const VEC_QM: &'static str = "vec_qm";
@@ -41,20 +32,18 @@ impl Circuit {
     pub const VEC_PI: &'static str = "vec_pi";
 }
 
-impl Circuit {
+impl<F: PrimeField> Circuit<F> {
     /// Adds a gate to the circuit.
     fn add_gate(
         &mut self,
-        a: (usize, usize, Fr),
-        b: (usize, usize, Fr),
-        c: (usize, usize, Fr),
-        gate_type: GateType,
-        pi: Fr,
+        a: (usize, usize, F),
+        b: (usize, usize, F),
+        c: (usize, usize, F),
+        gate_type: GateType<F>,
+        pi: F,
     ) {
-        // Push the values to the corresponding vectors
-        Arc::get_mut(&mut self.vals[0]).unwrap().push(a.2);
-        Arc::get_mut(&mut self.vals[1]).unwrap().push(b.2);
-        Arc::get_mut(&mut self.vals[2]).unwrap().push(c.2);
+        // Push the values to the witness
+        self.witness.push(a.2, b.2, c.2);
 
         // Create the gate based on the gate type and push it to the gates vector
         let gate = match gate_type {
@@ -70,13 +59,21 @@ impl Circuit {
                 Position::Pos(c.0, c.1),
                 Some(pi),
             ),
-            GateType::Constant => Gate::new_constant_gate(
+            GateType::Constant(constant) => Gate::new_constant_gate(
                 Position::Pos(a.0, a.1),
                 Position::Pos(b.0, b.1),
                 Position::Pos(c.0, c.1),
-                a.2,
+                constant,
                 Some(pi),
             ),
+            GateType::PublicInput => {
+                self.public_input_rows.push(self.gates.len());
+                Gate::new_public_input_gate(
+                    Position::Pos(a.0, a.1),
+                    Position::Pos(b.0, b.1),
+                    Position::Pos(c.0, c.1),
+                )
+            }
         };
         self.gates.push(gate);
     }
@@ -84,10 +81,10 @@ impl Circuit {
     /// Adds an addition gate to the circuit.
     pub fn add_addition_gate(
         &mut self,
-        a: (usize, usize, Fr),
-        b: (usize, usize, Fr),
-        c: (usize, usize, Fr),
-        pi: Fr,
+        a: (usize, usize, F),
+        b: (usize, usize, F),
+        c: (usize, usize, F),
+        pi: F,
     ) {
         self.add_gate(a, b, c, GateType::Addition, pi);
     }
@@ -95,45 +92,66 @@ impl Circuit {
     /// Adds a multiplication gate to the circuit.
     pub fn add_multiplication_gate(
         &mut self,
-        a: (usize, usize, Fr),
-        b: (usize, usize, Fr),
-        c: (usize, usize, Fr),
-        pi: Fr,
+        a: (usize, usize, F),
+        b: (usize, usize, F),
+        c: (usize, usize, F),
+        pi: F,
     ) {
         self.add_gate(a, b, c, GateType::Multiplication, pi);
     }
 
-    /// Adds a constant gate to the circuit.
+    /// Adds a constant gate to the circuit, constraining wire A to `constant`.
     pub fn add_constant_gate(
         &mut self,
-        a: (usize, usize, Fr),
-        b: (usize, usize, Fr),
-        c: (usize, usize, Fr),
-        pi: Fr,
+        a: (usize, usize, F),
+        b: (usize, usize, F),
+        c: (usize, usize, F),
+        constant: F,
+        pi: F,
+    ) {
+        self.add_gate(a, b, c, GateType::Constant(constant), pi);
+    }
+
+    /// Adds a public input gate to the circuit, exposing the value of wire A.
+    ///
+    /// The value is not part of the circuit, it is given to the prover and the verifier
+    /// as a public input, in the order the public input gates were added.
+    pub fn add_public_input_gate(
+        &mut self,
+        a: (usize, usize, F),
+        b: (usize, usize, F),
+        c: (usize, usize, F),
     ) {
-        self.add_gate(a, b, c, GateType::Constant, pi);
+        self.add_gate(a, b, c, GateType::PublicInput, F::zero());
+    }
+
+    /// Returns the values of the wires given with the gates.
+    pub fn witness(&self) -> &Witness<F> {
+        &self.witness
     }
 
-    /// Gets the assignment of the circuit.
-    pub(crate) fn get_assignment(&self) -> HashMap<&'static str, Vec<Fr>> {
+    /// Returns the values of the public inputs given with the gates.
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.public_input_rows
+            .iter()
+            .map(|row| self.witness.a()[*row])
+            .collect()
+    }
+
+    /// Gets the selector values of the given gates.
+    fn get_assignment(gates: &[Gate<F>]) -> HashMap<&'static str, Vec<F>> {
         let mut result = HashMap::default();
-        result.insert(Circuit::VEC_A, vec![]);
-        result.insert(Circuit::VEC_B, vec![]);
-        result.insert(Circuit::VEC_C, vec![]);
-        result.insert(Circuit::VEC_QL, vec![]);
-        result.insert(Circuit::VEC_QR, vec![]);
-        result.insert(Circuit::VEC_QM, vec![]);
-        result.insert(Circuit::VEC_QO, vec![]);
-        result.insert(Circuit::VEC_QC, vec![]);
-        result.insert(Circuit::VEC_PI, vec![]);
-
-        for (i, gate) in self.gates.iter().enumerate() {
+        result.insert(Self::VEC_QL, vec![]);
+        result.insert(Self::VEC_QR, vec![]);
+        result.insert(Self::VEC_QM, vec![]);
+        result.insert(Self::VEC_QO, vec![]);
+        result.insert(Self::VEC_QC, vec![]);
+        result.insert(Self::VEC_PI, vec![]);
+
+        for gate in gates.iter() {
             if gate.is_dummy_gate() {
                 continue;
             }
-            result.get_mut(Self::VEC_A).unwrap().push(self.vals[0][i]);
-            result.get_mut(Self::VEC_B).unwrap().push(self.vals[1][i]);
-            result.get_mut(Self::VEC_C).unwrap().push(self.vals[2][i]);
             result.get_mut(Self::VEC_QL).unwrap().push(gate.q_l);
             result.get_mut(Self::VEC_QR).unwrap().push(gate.q_r);
             result.get_mut(Self::VEC_QM).unwrap().push(gate.q_m);
@@ -144,76 +162,70 @@ impl Circuit {
         result
     }
 
-    /// Pads the circuit with dummy gates to make its size a power of 2.
-    fn pad_circuit(&mut self) {
-        let len = self.gates.len();
+    /// Returns the gates padded with dummy gates to make their number a power of 2.
+    fn padded_gates(&self) -> Vec<Gate<F>> {
+        let mut gates = self.gates.clone();
+        let len = gates.len();
 
         let exponent = (len - 1).ilog2() + 1;
         let new_len = 1 << exponent;
 
-        for _ in len..new_len {
-            self.add_dummy_gate();
-        }
+        gates.resize(new_len, Gate::new_dummy_gate());
+        gates
     }
 
-    /// Adds a dummy gate to the circuit.
-    fn add_dummy_gate(&mut self) {
-        let gate = Gate::new_dummy_gate();
-        self.gates.push(gate);
-    }
+    /// Compiles the structure of the circuit into a compiled circuit.
+    ///
+    /// The values of the wires are not part of the compiled circuit, so it can be proven
+    /// with any witness of the circuit.
+    pub fn compile(&self) -> Result<CompiledCircuit<F>, String> {
+        let gates = self.padded_gates();
 
-    /// Compiles the circuit into a compiled circuit.
-    pub fn compile(mut self) -> Result<CompiledCircuit, String> {
-        self.pad_circuit();
+        let circuit_size = gates.len();
+        let domain = GeneralEvaluationDomain::<F>::new(circuit_size).unwrap();
+        let assignment = Self::get_assignment(&gates);
 
-        let circuit_size = self.gates.len();
-        let domain = GeneralEvaluationDomain::<Fr>::new(circuit_size).unwrap();
-        let assignment = self.get_assignment();
-
-        let mut interpolated_assignment = assignment
-            .into_iter()
+        let mut interpolated_assignment = cfg_into_iter!(assignment)
             .map(|(k, v)| (k, Evaluations::from_vec_and_domain(v, domain).interpolate()))
             .collect::<HashMap<_, _>>();
 
         let gate_constraints = GateConstraints::new(
-            interpolated_assignment.remove(Circuit::VEC_A).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_B).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_C).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_QL).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_QR).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_QO).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_QM).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_QC).unwrap(),
-            interpolated_assignment.remove(Circuit::VEC_PI).unwrap(),
+            interpolated_assignment.remove(Self::VEC_QL).unwrap(),
+            interpolated_assignment.remove(Self::VEC_QR).unwrap(),
+            interpolated_assignment.remove(Self::VEC_QO).unwrap(),
+            interpolated_assignment.remove(Self::VEC_QM).unwrap(),
+            interpolated_assignment.remove(Self::VEC_QC).unwrap(),
+            interpolated_assignment.remove(Self::VEC_PI).unwrap(),
         );
 
-        let copy_constraints = self.cal_permutation();
+        let copy_constraints = Self::cal_permutation(&gates);
 
         Ok(CompiledCircuit::new(
             gate_constraints,
             copy_constraints,
             circuit_size,
+            self.public_input_rows.clone(),
         ))
     }
 
     /// Calculates the Copy constraints.
-    fn cal_permutation(&self) -> CopyConstraints {
-        let len = self.gates.len();
-        let domain = GeneralEvaluationDomain::<Fr>::new(len).unwrap();
+    fn cal_permutation(gates: &[Gate<F>]) -> CopyConstraints<F> {
+        let len = gates.len();
+        let domain = GeneralEvaluationDomain::<F>::new(len).unwrap();
         let roots = domain.elements().collect::<Vec<_>>();
-        let (coset1, coset2, k1, k2) = self.find_cosets(&roots);
+        let (coset1, coset2, k1, k2) = Self::find_cosets(&roots);
 
         // create sigma_1, sigma_2, and sigma_3
         let mut sigma_1 = roots.clone();
         let mut sigma_2 = coset1.clone();
         let mut sigma_3 = coset2.clone();
 
-        for (index, gate) in self.gates.iter().enumerate() {
+        for (index, gate) in gates.iter().enumerate() {
             if gate.is_dummy_gate() {
                 continue;
             }
 
-            let map_element = |pos: &Position| -> Fr {
+            let map_element = |pos: &Position| -> F {
                 match pos {
                     Position::Pos(0, i_2) => roots[*i_2],
                     Position::Pos(1, i_2) => coset1[*i_2],
@@ -227,17 +239,20 @@ impl Circuit {
             sigma_3[index] = map_element(gate.get_c_wire());
         }
 
-        let s_sigma_1 = Evaluations::from_vec_and_domain(sigma_1, domain).interpolate();
-        let s_sigma_2 = Evaluations::from_vec_and_domain(sigma_2, domain).interpolate();
-        let s_sigma_3 = Evaluations::from_vec_and_domain(sigma_3, domain).interpolate();
+        let mut sigmas = cfg_into_iter!(vec![sigma_1, sigma_2, sigma_3])
+            .map(|sigma| Evaluations::from_vec_and_domain(sigma, domain).interpolate())
+            .collect::<Vec<_>>();
+        let s_sigma_3 = sigmas.pop().unwrap();
+        let s_sigma_2 = sigmas.pop().unwrap();
+        let s_sigma_1 = sigmas.pop().unwrap();
 
         CopyConstraints::new(s_sigma_1, s_sigma_2, s_sigma_3, k1, k2)
     }
 
     /// Finds the cosets for permutation.
-    fn find_cosets(&self, roots: &[Fr]) -> (Vec<Fr>, Vec<Fr>, Fr, Fr) {
-        let k1 = roots[0] + Fr::one();
-        let k2 = k1 + Fr::one();
+    fn find_cosets(roots: &[F]) -> (Vec<F>, Vec<F>, F, F) {
+        let k1 = roots[0] + F::one();
+        let k2 = k1 + F::one();
         let coset1 = roots.iter().map(|root| *root * k1).collect();
         let coset2 = roots.iter().map(|root| *root * k2).collect();
 
@@ -245,6 +260,14 @@ impl Circuit {
     }
 }
 
+/// Enum representing different types of gates.
+enum GateType<F> {
+    Addition,
+    Multiplication,
+    Constant(F),
+    PublicInput,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,13 +300,6 @@ mod tests {
             Fr::from(0),
         );
 
-        assert_eq!(circuit.vals[0][2], circuit.vals[2][1]);
+        assert_eq!(circuit.witness().a()[2], circuit.witness().c()[1]);
     }
 }
-
-/// Enum representing different types of gates.
-enum GateType {
-    Addition,
-    Multiplication,
-    Constant,
-}
@@ -1,2 +1 @@
-pub mod cpi_circuit;
 pub mod cpi_parser;
@@ -1,18 +1,25 @@
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use crate::constraint::{CopyConstraints, GateConstraints};
 
 /// Represents a compiled circuit with gate and copy constraints.
-#[derive(Debug, CanonicalDeserialize, CanonicalSerialize)]
-pub struct CompiledCircuit {
+///
+/// It only describes the structure of the circuit, the values of the wires are given
+/// by a `Witness` for each proof.
+#[derive(Debug, PartialEq, CanonicalDeserialize, CanonicalSerialize)]
+pub struct CompiledCircuit<F: PrimeField = Fr> {
     /// The size of the compiled circuit.
     pub size: usize,
 
-    gate_constraint: GateConstraints,
-    copy_constraint: CopyConstraints,
+    gate_constraint: GateConstraints<F>,
+    copy_constraint: CopyConstraints<F>,
+    /// Rows of the public input gates, in the order of the public inputs.
+    public_input_rows: Vec<usize>,
 }
 
-impl CompiledCircuit {
+impl<F: PrimeField> CompiledCircuit<F> {
     /// Creates a new `CompiledCircuit` instance.
     ///
     /// # Parameters
@@ -20,25 +27,33 @@ impl CompiledCircuit {
     /// - `gate_constraint`: The gate constraints of the compiled circuit.
     /// - `copy_constraint`: The copy constraints of the compiled circuit.
     /// - `size`: The size of the compiled circuit.
+    /// - `public_input_rows`: The rows of the public input gates.
     pub fn new(
-        gate_constraint: GateConstraints,
-        copy_constraint: CopyConstraints,
+        gate_constraint: GateConstraints<F>,
+        copy_constraint: CopyConstraints<F>,
         size: usize,
+        public_input_rows: Vec<usize>,
     ) -> Self {
         Self {
             gate_constraint,
             copy_constraint,
             size,
+            public_input_rows,
         }
     }
 
     /// Returns a reference to the gate constraints of the compiled circuit.
-    pub fn gate_constraints(&self) -> &GateConstraints {
+    pub fn gate_constraints(&self) -> &GateConstraints<F> {
         &self.gate_constraint
     }
 
     /// Returns a reference to the copy constraints of the compiled circuit.
-    pub fn copy_constraints(&self) -> &CopyConstraints {
+    pub fn copy_constraints(&self) -> &CopyConstraints<F> {
         &self.copy_constraint
     }
+
+    /// Returns the rows of the public input gates, in the order of the public inputs.
+    pub fn public_input_rows(&self) -> &[usize] {
+        &self.public_input_rows
+    }
 }
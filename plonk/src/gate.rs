@@ -1,5 +1,5 @@
 use ark_bls12_381::Fr;
-use ark_ff::{One, Zero};
+use ark_ff::PrimeField;
 
 /// Enum representing the position of a wire in a gate.
 #[derive(Clone, PartialEq, Debug)]
@@ -12,7 +12,7 @@ pub enum Position {
 
 #[derive(Clone, PartialEq, Debug)]
 /// Struct representing a gate in the circuit.
-pub struct Gate {
+pub struct Gate<F: PrimeField = Fr> {
     /// Position of the input wire A.
     a_pos: Position,
     /// Position of the input wire B.
@@ -20,37 +20,37 @@ pub struct Gate {
     /// Position of the output wire C.
     c_pos: Position,
     /// Q_L coefficient.
-    pub(crate) q_l: Fr,
+    pub(crate) q_l: F,
     /// Q_R coefficient.
-    pub(crate) q_r: Fr,
+    pub(crate) q_r: F,
     /// Q_O coefficient.
-    pub(crate) q_o: Fr,
+    pub(crate) q_o: F,
     /// Q_M coefficient.
-    pub(crate) q_m: Fr,
+    pub(crate) q_m: F,
     /// Q_C coefficient.
-    pub(crate) q_c: Fr,
+    pub(crate) q_c: F,
     /// Pi coefficient.
-    pub(crate) pi: Fr,
+    pub(crate) pi: F,
 }
 
-impl Gate {
+impl<F: PrimeField> Gate<F> {
     /// Creates a new addition gate.
     pub(crate) fn new_add_gate(
         a_pos: Position,
         b_pos: Position,
         c_pos: Position,
-        pi: Option<Fr>,
+        pi: Option<F>,
     ) -> Self {
         Self {
             a_pos,
             b_pos,
             c_pos,
-            q_l: Fr::one(),
-            q_r: Fr::one(),
-            q_m: Fr::zero(),
-            q_o: -Fr::one(),
-            q_c: Fr::zero(),
-            pi: -pi.unwrap_or(Fr::zero()),
+            q_l: F::one(),
+            q_r: F::one(),
+            q_m: F::zero(),
+            q_o: -F::one(),
+            q_c: F::zero(),
+            pi: -pi.unwrap_or(F::zero()),
         }
     }
 
@@ -59,18 +59,18 @@ impl Gate {
         a_pos: Position,
         b_pos: Position,
         c_pos: Position,
-        pi: Option<Fr>,
+        pi: Option<F>,
     ) -> Self {
         Self {
             a_pos,
             b_pos,
             c_pos,
-            q_l: Fr::zero(),
-            q_r: Fr::zero(),
-            q_m: Fr::one(),
-            q_o: -Fr::one(),
-            q_c: Fr::zero(),
-            pi: -pi.unwrap_or(Fr::zero()),
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_m: F::one(),
+            q_o: -F::one(),
+            q_c: F::zero(),
+            pi: -pi.unwrap_or(F::zero()),
         }
     }
 
@@ -79,19 +79,37 @@ impl Gate {
         a_pos: Position,
         b_pos: Position,
         c_pos: Position,
-        constant: Fr,
-        pi: Option<Fr>,
+        constant: F,
+        pi: Option<F>,
     ) -> Self {
         Self {
             a_pos,
             b_pos,
             c_pos,
-            q_l: Fr::one(),
-            q_r: Fr::zero(),
-            q_m: Fr::zero(),
-            q_o: Fr::zero(),
+            q_l: F::one(),
+            q_r: F::zero(),
+            q_m: F::zero(),
+            q_o: F::zero(),
             q_c: -constant,
-            pi: -pi.unwrap_or(Fr::zero()),
+            pi: -pi.unwrap_or(F::zero()),
+        }
+    }
+
+    /// Creates a new public input gate.
+    ///
+    /// The public input PI(X) of its row holds the negated value of wire A, so it only
+    /// selects wire A.
+    pub(crate) fn new_public_input_gate(a_pos: Position, b_pos: Position, c_pos: Position) -> Self {
+        Self {
+            a_pos,
+            b_pos,
+            c_pos,
+            q_l: F::one(),
+            q_r: F::zero(),
+            q_m: F::zero(),
+            q_o: F::zero(),
+            q_c: F::zero(),
+            pi: F::zero(),
         }
     }
 
@@ -101,12 +119,12 @@ impl Gate {
             a_pos: Position::Dummy,
             b_pos: Position::Dummy,
             c_pos: Position::Dummy,
-            q_l: Fr::zero(),
-            q_r: Fr::zero(),
-            q_m: Fr::zero(),
-            q_o: Fr::zero(),
-            q_c: Fr::zero(),
-            pi: Fr::zero(),
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_m: F::zero(),
+            q_o: F::zero(),
+            q_c: F::zero(),
+            pi: F::zero(),
         }
     }
 
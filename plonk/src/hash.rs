@@ -0,0 +1,181 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use ark_bls12_381::Fr;
+use ark_crypto_primitives::sponge::poseidon::{
+    find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge,
+};
+use ark_crypto_primitives::sponge::CryptographicSponge;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use digest::consts::U32;
+use digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+
+/// Hash functions a transcript can be instantiated with.
+///
+/// The prover and the verifier must use the same one, so it is a parameter of the
+/// generated verifier contract too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TranscriptHash {
+    #[default]
+    Sha256,
+    /// Blake2b with the personalisation of CKB, see [`CkbBlake2b`].
+    Blake2b,
+    /// Poseidon over the scalar field of BLS12-381, see [`Poseidon`].
+    Poseidon,
+}
+
+impl TranscriptHash {
+    /// Returns the name of the hash function, as parsed by `from_str`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TranscriptHash::Sha256 => "sha256",
+            TranscriptHash::Blake2b => "blake2b",
+            TranscriptHash::Poseidon => "poseidon",
+        }
+    }
+}
+
+impl fmt::Display for TranscriptHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TranscriptHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "sha256" => Ok(TranscriptHash::Sha256),
+            "blake2b" => Ok(TranscriptHash::Blake2b),
+            "poseidon" => Ok(TranscriptHash::Poseidon),
+            _ => Err(format!(
+                "Transcript hash: unknown hash function {s}, expected sha256, blake2b or poseidon"
+            )),
+        }
+    }
+}
+
+/// Blake2b with a 32 bytes output and the `ckb-default-hash` personalisation.
+///
+/// This is the hash of `ckb_hash::blake2b_256`, which CKB scripts already link.
+#[derive(Clone)]
+pub struct CkbBlake2b(blake2b_simd::State);
+
+impl Default for CkbBlake2b {
+    fn default() -> Self {
+        Self(
+            blake2b_simd::Params::new()
+                .hash_length(32)
+                .personal(b"ckb-default-hash")
+                .to_state(),
+        )
+    }
+}
+
+impl HashMarker for CkbBlake2b {}
+
+impl Update for CkbBlake2b {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+}
+
+impl OutputSizeUser for CkbBlake2b {
+    type OutputSize = U32;
+}
+
+impl FixedOutput for CkbBlake2b {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(self.0.finalize().as_bytes());
+    }
+}
+
+/// Poseidon over the scalar field of BLS12-381, hashing bytes.
+///
+/// The bytes are packed in field elements of 31 bytes followed by their length, absorbed
+/// in a sponge of rate 2 with the S-box x^5, 8 full rounds and 57 partial rounds, and the
+/// output is the little-endian encoding of one squeezed element.
+#[derive(Clone, Default)]
+pub struct Poseidon(Vec<u8>);
+
+impl Poseidon {
+    /// Returns the parameters of the sponge, generated once with the Grain LFSR.
+    fn config() -> &'static PoseidonConfig<Fr> {
+        static CONFIG: OnceLock<PoseidonConfig<Fr>> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            let (ark, mds) =
+                find_poseidon_ark_and_mds::<Fr>(Fr::MODULUS_BIT_SIZE as u64, 2, 8, 57, 0);
+            PoseidonConfig::new(8, 57, 5, mds, ark, 2, 1)
+        })
+    }
+}
+
+impl HashMarker for Poseidon {}
+
+impl Update for Poseidon {
+    fn update(&mut self, data: &[u8]) {
+        self.0.extend_from_slice(data);
+    }
+}
+
+impl OutputSizeUser for Poseidon {
+    type OutputSize = U32;
+}
+
+impl FixedOutput for Poseidon {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        let mut elements = self
+            .0
+            .chunks(31)
+            .map(Fr::from_le_bytes_mod_order)
+            .collect::<Vec<_>>();
+        elements.push(Fr::from(self.0.len() as u64));
+
+        let mut sponge = PoseidonSponge::new(Self::config());
+        sponge.absorb(&elements);
+        let hash = sponge.squeeze_field_elements::<Fr>(1)[0];
+        hash.serialize_uncompressed(&mut out[..])
+            .expect("A scalar should fit in 32 bytes!");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use digest::Digest;
+
+    use super::*;
+
+    #[test]
+    fn ckb_blake2b_test() {
+        // The hash of the empty message of CKB, `ckb_hash::BLANK_HASH`.
+        assert_eq!(
+            CkbBlake2b::digest(b"")[..],
+            [
+                0x44, 0xf4, 0xc6, 0x97, 0x44, 0xd5, 0xf8, 0xc5, 0x5d, 0x64, 0x20, 0x62, 0x94, 0x9d,
+                0xca, 0xe4, 0x9b, 0xc4, 0xe7, 0xef, 0x43, 0xd3, 0x88, 0xc5, 0xa1, 0x2f, 0x42, 0xb5,
+                0x63, 0x3d, 0x16, 0x3e,
+            ]
+        );
+    }
+
+    #[test]
+    fn poseidon_test() {
+        let hash = Poseidon::digest(b"plonk");
+        assert_eq!(
+            hash,
+            Poseidon::new()
+                .chain_update(b"pl")
+                .chain_update(b"onk")
+                .finalize()
+        );
+        assert_ne!(
+            hash,
+            Poseidon::digest(b"plonk\0"),
+            "the length should be absorbed"
+        );
+        assert_ne!(hash, Poseidon::digest(b""));
+    }
+}
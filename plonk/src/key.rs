@@ -0,0 +1,478 @@
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ff::{batch_inversion, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, GeneralEvaluationDomain, Polynomial as Poly};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::cfg_iter;
+use digest::Digest;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use kzg::commitment::KzgCommitment;
+use kzg::scheme::KzgScheme;
+use kzg::srs::Srs;
+
+use crate::compiled_circuit::CompiledCircuit;
+use crate::prover::l1_poly;
+use crate::slice_polynomial::slice_size;
+use crate::transcript::Transcript;
+use crate::types::Polynomial;
+
+/// Preprocessed data of a circuit needed by the prover.
+///
+/// It holds the selector and permutation polynomials of the circuit together with their
+/// evaluations, so that proving does not interpolate or evaluate them again, and the
+/// verifying key the proofs are bound to.
+#[derive(Debug, PartialEq, CanonicalDeserialize, CanonicalSerialize)]
+pub struct ProvingKey<E: Pairing = Bls12_381> {
+    /// The size of the circuit.
+    pub n: usize,
+    /// First constant factor used in copy constraint calculations.
+    pub k1: E::ScalarField,
+    /// Second constant factor used in copy constraint calculations.
+    pub k2: E::ScalarField,
+    pub q_lx: Polynomial<E::ScalarField>,
+    pub q_rx: Polynomial<E::ScalarField>,
+    pub q_mx: Polynomial<E::ScalarField>,
+    pub q_ox: Polynomial<E::ScalarField>,
+    pub q_cx: Polynomial<E::ScalarField>,
+    pub s_sigma_1: Polynomial<E::ScalarField>,
+    pub s_sigma_2: Polynomial<E::ScalarField>,
+    pub s_sigma_3: Polynomial<E::ScalarField>,
+    /// Constant part of the public input polynomial, given by the gates.
+    pub pi_x: Polynomial<E::ScalarField>,
+    /// Rows of the public input gates, in the order of the public inputs.
+    pub public_input_rows: Vec<usize>,
+    /// The verifying key of the circuit, absorbed in the transcript of each proof.
+    pub verifying_key: VerifyingKey<E>,
+    /// Values of the permutation polynomials over the evaluation domain.
+    pub(crate) s_sigma_evals: [Vec<E::ScalarField>; 3],
+    /// Evaluations over the coset the quotient polynomial is computed on.
+    pub(crate) coset_evals: CosetEvaluations<E::ScalarField>,
+    /// Commitments to the Lagrange basis of the domain, to commit the wires from their values.
+    pub(crate) lagrange_basis: Vec<E::G1Affine>,
+}
+
+/// Evaluations of the preprocessed polynomials over the quotient coset.
+#[derive(Debug, PartialEq, CanonicalDeserialize, CanonicalSerialize)]
+pub(crate) struct CosetEvaluations<F: PrimeField> {
+    pub(crate) q_l: Vec<F>,
+    pub(crate) q_r: Vec<F>,
+    pub(crate) q_m: Vec<F>,
+    pub(crate) q_o: Vec<F>,
+    pub(crate) q_c: Vec<F>,
+    pub(crate) s_sigma_1: Vec<F>,
+    pub(crate) s_sigma_2: Vec<F>,
+    pub(crate) s_sigma_3: Vec<F>,
+    pub(crate) l1: Vec<F>,
+}
+
+impl<E: Pairing> ProvingKey<E> {
+    /// Preprocesses a compiled circuit into a proving key.
+    ///
+    /// # Parameters
+    ///
+    /// - `compiled_circuit`: The circuit to preprocess.
+    /// - `verifying_key`: The verifying key of the circuit.
+    /// - `scheme`: The KZG scheme the Lagrange basis of the domain is computed with.
+    ///
+    /// # Returns
+    ///
+    /// The proving key, or an error if the SRS is too small for the circuit.
+    pub fn new(
+        compiled_circuit: &CompiledCircuit<E::ScalarField>,
+        verifying_key: VerifyingKey<E>,
+        scheme: &KzgScheme<E>,
+    ) -> Result<Self, String> {
+        let n = compiled_circuit.size;
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(n).unwrap();
+        let coset = quotient_domain::<E::ScalarField>(n);
+        let gate_constraints = compiled_circuit.gate_constraints();
+        let copy_constraints = compiled_circuit.copy_constraints();
+
+        let s_sigmas = [
+            copy_constraints.s_sigma_1(),
+            copy_constraints.s_sigma_2(),
+            copy_constraints.s_sigma_3(),
+        ];
+        let s_sigma_evals = s_sigmas.map(|sigma| domain.fft(sigma));
+
+        let l1 = l1_poly(&domain);
+        let polys = [
+            gate_constraints.q_lx(),
+            gate_constraints.q_rx(),
+            gate_constraints.q_mx(),
+            gate_constraints.q_ox(),
+            gate_constraints.q_cx(),
+            copy_constraints.s_sigma_1(),
+            copy_constraints.s_sigma_2(),
+            copy_constraints.s_sigma_3(),
+            &l1,
+        ];
+        let mut evals = cfg_iter!(polys)
+            .map(|poly| coset.fft(poly))
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut next = || evals.next().unwrap();
+        let coset_evals = CosetEvaluations {
+            q_l: next(),
+            q_r: next(),
+            q_m: next(),
+            q_o: next(),
+            q_c: next(),
+            s_sigma_1: next(),
+            s_sigma_2: next(),
+            s_sigma_3: next(),
+            l1: next(),
+        };
+
+        let lagrange_basis = scheme.lagrange_basis(domain)?;
+
+        Ok(Self {
+            n,
+            k1: *copy_constraints.k1(),
+            k2: *copy_constraints.k2(),
+            q_lx: gate_constraints.q_lx().clone(),
+            q_rx: gate_constraints.q_rx().clone(),
+            q_mx: gate_constraints.q_mx().clone(),
+            q_ox: gate_constraints.q_ox().clone(),
+            q_cx: gate_constraints.q_cx().clone(),
+            s_sigma_1: copy_constraints.s_sigma_1().clone(),
+            s_sigma_2: copy_constraints.s_sigma_2().clone(),
+            s_sigma_3: copy_constraints.s_sigma_3().clone(),
+            pi_x: gate_constraints.pi_x().clone(),
+            public_input_rows: compiled_circuit.public_input_rows().to_vec(),
+            verifying_key,
+            s_sigma_evals,
+            coset_evals,
+            lagrange_basis,
+        })
+    }
+
+    /// Returns the public input polynomial PI(X) for the given public inputs.
+    ///
+    /// Fails if the number of public inputs does not match the public input gates.
+    pub fn public_input_poly(
+        &self,
+        public_inputs: &[E::ScalarField],
+    ) -> Result<Polynomial<E::ScalarField>, String> {
+        check_public_inputs(&self.public_input_rows, public_inputs)?;
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(self.n).unwrap();
+        let mut evals = vec![E::ScalarField::zero(); self.n];
+        for (row, value) in self.public_input_rows.iter().zip(public_inputs) {
+            evals[*row] = -*value;
+        }
+        Ok(&self.pi_x + &Evaluations::from_vec_and_domain(evals, domain).interpolate())
+    }
+}
+
+/// Preprocessed data of a circuit needed by the verifier.
+///
+/// It holds the commitments of the selector and permutation polynomials and the part of
+/// the SRS used to verify openings. The SRS is validated when the key is built or
+/// deserialized with validation, not on each proof.
+#[derive(Debug, Clone, PartialEq, CanonicalDeserialize, CanonicalSerialize)]
+pub struct VerifyingKey<E: Pairing = Bls12_381> {
+    /// The size of the circuit.
+    pub n: usize,
+    /// First constant factor used in copy constraint calculations.
+    pub k1: E::ScalarField,
+    /// Second constant factor used in copy constraint calculations.
+    pub k2: E::ScalarField,
+    /// Generator of the evaluation domain.
+    pub omega: E::ScalarField,
+    pub com_q_lx: KzgCommitment<E>,
+    pub com_q_rx: KzgCommitment<E>,
+    pub com_q_mx: KzgCommitment<E>,
+    pub com_q_ox: KzgCommitment<E>,
+    pub com_q_cx: KzgCommitment<E>,
+    pub com_s_sigma_1: KzgCommitment<E>,
+    pub com_s_sigma_2: KzgCommitment<E>,
+    pub com_s_sigma_3: KzgCommitment<E>,
+    /// Constant part of the public input polynomial, given by the gates.
+    pub pi_x: Polynomial<E::ScalarField>,
+    /// Rows of the public input gates, in the order of the public inputs.
+    pub public_input_rows: Vec<usize>,
+    /// The verifier subset of the SRS the commitments were made with.
+    pub srs: Srs<E>,
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+    /// Commits to the preprocessed polynomials of a compiled circuit.
+    ///
+    /// # Parameters
+    ///
+    /// - `compiled_circuit`: The circuit to preprocess.
+    /// - `srs`: The SRS to commit with.
+    ///
+    /// # Returns
+    ///
+    /// The verifying key, or an error if the SRS is too small for the circuit.
+    pub fn new(
+        compiled_circuit: &CompiledCircuit<E::ScalarField>,
+        srs: &Srs<E>,
+    ) -> Result<Self, String> {
+        Self::with_scheme(compiled_circuit, &KzgScheme::new(srs.clone()))
+    }
+
+    /// Commits to the preprocessed polynomials of a compiled circuit with a KZG scheme.
+    ///
+    /// This allows committing with a scheme that does not hold the SRS in memory, such as
+    /// one created with `KzgScheme::new_mapped`.
+    pub fn with_scheme(
+        compiled_circuit: &CompiledCircuit<E::ScalarField>,
+        scheme: &KzgScheme<E>,
+    ) -> Result<Self, String> {
+        let gate_constraints = compiled_circuit.gate_constraints();
+        let copy_constraints = compiled_circuit.copy_constraints();
+        let [com_q_lx, com_q_rx, com_q_mx, com_q_ox, com_q_cx, com_s_sigma_1, com_s_sigma_2, com_s_sigma_3] =
+            scheme.commit_all([
+                gate_constraints.q_lx(),
+                gate_constraints.q_rx(),
+                gate_constraints.q_mx(),
+                gate_constraints.q_ox(),
+                gate_constraints.q_cx(),
+                copy_constraints.s_sigma_1(),
+                copy_constraints.s_sigma_2(),
+                copy_constraints.s_sigma_3(),
+            ])?;
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(compiled_circuit.size).unwrap();
+        // Checked once here, so that verifying a proof does not check it again.
+        let srs = scheme.verifier_srs();
+        srs.validate()?;
+
+        Ok(Self {
+            n: compiled_circuit.size,
+            k1: *copy_constraints.k1(),
+            k2: *copy_constraints.k2(),
+            omega: domain.group_gen(),
+            com_q_lx,
+            com_q_rx,
+            com_q_mx,
+            com_q_ox,
+            com_q_cx,
+            com_s_sigma_1,
+            com_s_sigma_2,
+            com_s_sigma_3,
+            pi_x: gate_constraints.pi_x().clone(),
+            public_input_rows: compiled_circuit.public_input_rows().to_vec(),
+            srs,
+        })
+    }
+
+    /// Absorbs the circuit of the verifying key in a transcript.
+    ///
+    /// This binds the challenges of a proof to the circuit it is verified against.
+    pub fn append_to_transcript<T: Digest>(&self, transcript: &mut Transcript<T>) {
+        transcript.append_message(b"n", &(self.n as u64).to_le_bytes());
+        transcript.append_scalar(b"k1", &self.k1);
+        transcript.append_scalar(b"k2", &self.k2);
+        for (label, commitment) in [
+            (b"q_l" as &'static [u8], &self.com_q_lx),
+            (b"q_r", &self.com_q_rx),
+            (b"q_m", &self.com_q_mx),
+            (b"q_o", &self.com_q_ox),
+            (b"q_c", &self.com_q_cx),
+            (b"s_sigma_1", &self.com_s_sigma_1),
+            (b"s_sigma_2", &self.com_s_sigma_2),
+            (b"s_sigma_3", &self.com_s_sigma_3),
+        ] {
+            transcript.append_point(label, commitment.inner());
+        }
+        let rows = self
+            .public_input_rows
+            .iter()
+            .flat_map(|row| (*row as u64).to_le_bytes())
+            .collect::<Vec<_>>();
+        transcript.append_message(b"public_input_rows", &rows);
+        let mut pi_x = Vec::new();
+        for coefficient in &self.pi_x.coeffs {
+            coefficient
+                .serialize_uncompressed(&mut pi_x)
+                .expect("Serializing to a vector should be infallible!");
+        }
+        transcript.append_message(b"pi_x", &pi_x);
+    }
+
+    /// Evaluates the public input polynomial PI(X) for the given public inputs at `point`.
+    ///
+    /// Only the Lagrange polynomials of the public input rows are evaluated, so this does
+    /// not depend on the size of the circuit. Fails if the number of public inputs does
+    /// not match the public input gates.
+    pub fn public_input_eval(
+        &self,
+        public_inputs: &[E::ScalarField],
+        point: E::ScalarField,
+    ) -> Result<E::ScalarField, String> {
+        check_public_inputs(&self.public_input_rows, public_inputs)?;
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(self.n).unwrap();
+        let z_h = domain.evaluate_vanishing_polynomial(point);
+        let n = E::ScalarField::from(self.n as u64);
+
+        // L_i(X) = ω^i (X^n - 1) / (n (X - ω^i))
+        let mut denominators = self
+            .public_input_rows
+            .iter()
+            .map(|row| n * (point - domain.element(*row)))
+            .collect::<Vec<_>>();
+        batch_inversion(&mut denominators);
+        let public = self
+            .public_input_rows
+            .iter()
+            .zip(public_inputs)
+            .zip(denominators)
+            .map(|((row, value), inverse)| *value * domain.element(*row) * z_h * inverse)
+            .sum::<E::ScalarField>();
+
+        Ok(self.pi_x.evaluate(&point) - public)
+    }
+}
+
+/// Preprocesses a compiled circuit into a proving key and a verifying key.
+///
+/// Both keys can be serialized, so that proving and verifying do not have to compile
+/// the circuit or commit to its polynomials again.
+///
+/// # Parameters
+///
+/// - `compiled_circuit`: The circuit to preprocess.
+/// - `srs`: The SRS to commit with.
+///
+/// # Returns
+///
+/// The proving key and the verifying key, or an error if the SRS is too small for the circuit.
+pub fn setup<E: Pairing>(
+    compiled_circuit: &CompiledCircuit<E::ScalarField>,
+    srs: &Srs<E>,
+) -> Result<(ProvingKey<E>, VerifyingKey<E>), String> {
+    let scheme = KzgScheme::new(srs.clone());
+    let verifying_key = VerifyingKey::with_scheme(compiled_circuit, &scheme)?;
+    Ok((
+        ProvingKey::new(compiled_circuit, verifying_key.clone(), &scheme)?,
+        verifying_key,
+    ))
+}
+
+/// Checks the number of public inputs matches the public input gates.
+fn check_public_inputs<F>(public_input_rows: &[usize], public_inputs: &[F]) -> Result<(), String> {
+    if public_inputs.len() != public_input_rows.len() {
+        return Err(format!(
+            "Public inputs: expected {} values, got {}",
+            public_input_rows.len(),
+            public_inputs.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the coset the quotient polynomial of a circuit of size `n` is computed on.
+///
+/// The blinded wire polynomials have degree at most n + 1 and the permutation polynomial
+/// at most n + 2, so the numerator of the quotient polynomial has degree at most 4n + 5 and
+/// the quotient polynomial, once divided by the vanishing polynomial, at most 3n + 5. The
+/// coset has at least its 3n + 6 coefficients, which rounds up to 4n points from n = 8.
+pub(crate) fn quotient_domain<F: PrimeField>(n: usize) -> GeneralEvaluationDomain<F> {
+    GeneralEvaluationDomain::<F>::new(3 * slice_size(n))
+        .and_then(|domain| domain.get_coset(F::GENERATOR))
+        .expect("The field has a domain for the quotient polynomial")
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_poly::DenseUVPolynomial;
+    use sha2::Sha256;
+
+    use crate::circuit::Circuit;
+    use crate::prover::{generate_proof_with_key, OpeningScheme};
+    use crate::verifier::verify_with_key;
+
+    use super::*;
+
+    #[test]
+    fn keys_roundtrip() {
+        // check xyz = 6
+        let mut circuit = Circuit::default();
+        circuit.add_multiplication_gate(
+            (0, 0, Fr::from(1)),
+            (1, 0, Fr::from(2)),
+            (0, 1, Fr::from(2)),
+            Fr::from(0),
+        );
+        circuit.add_multiplication_gate(
+            (2, 0, Fr::from(2)),
+            (1, 1, Fr::from(3)),
+            (2, 1, Fr::from(6)),
+            Fr::from(0),
+        );
+        let compiled_circuit = circuit.compile().unwrap();
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+        let (proving_key, verifying_key) = setup(&compiled_circuit, &srs).unwrap();
+
+        let mut proving_key_bytes = Vec::new();
+        proving_key
+            .serialize_uncompressed(&mut proving_key_bytes)
+            .unwrap();
+        let proving_key =
+            ProvingKey::<Bls12_381>::deserialize_uncompressed(&proving_key_bytes[..]).unwrap();
+        let mut verifying_key_bytes = Vec::new();
+        verifying_key
+            .serialize_compressed(&mut verifying_key_bytes)
+            .unwrap();
+        let verifying_key =
+            VerifyingKey::<Bls12_381>::deserialize_compressed(&verifying_key_bytes[..]).unwrap();
+        assert_eq!(verifying_key.srs.g1_points().len(), 1);
+
+        let proof = generate_proof_with_key::<Sha256, _>(
+            &proving_key,
+            circuit.witness(),
+            &[],
+            KzgScheme::new(srs.clone()),
+            OpeningScheme::Kzg,
+        )
+        .unwrap();
+        assert!(
+            verify_with_key::<Sha256, _>(&verifying_key, proof, &[], OpeningScheme::Kzg).is_ok()
+        );
+
+        // A verifying key of another circuit rejects the proof.
+        let mut other = Circuit::default();
+        other.add_multiplication_gate(
+            (0, 0, Fr::from(1)),
+            (1, 0, Fr::from(2)),
+            (0, 1, Fr::from(2)),
+            Fr::from(0),
+        );
+        other.add_addition_gate(
+            (2, 0, Fr::from(2)),
+            (1, 1, Fr::from(3)),
+            (2, 1, Fr::from(5)),
+            Fr::from(0),
+        );
+        let other = other.compile().unwrap();
+        let (_, other_verifying_key) = setup(&other, &srs).unwrap();
+        let proof = generate_proof_with_key::<Sha256, _>(
+            &proving_key,
+            circuit.witness(),
+            &[],
+            KzgScheme::new(srs),
+            OpeningScheme::Kzg,
+        )
+        .unwrap();
+        assert!(
+            verify_with_key::<Sha256, _>(&other_verifying_key, proof, &[], OpeningScheme::Kzg)
+                .is_err()
+        );
+
+        // A verifying key that only differs in its constant public input polynomial derives
+        // other challenges.
+        let challenge = |verifying_key: &VerifyingKey| {
+            let mut transcript = Transcript::<Sha256>::new(b"test");
+            verifying_key.append_to_transcript(&mut transcript);
+            transcript.challenge_scalar::<Fr>(b"challenge")
+        };
+        let mut shifted = verifying_key.clone();
+        shifted.pi_x = &shifted.pi_x + &Polynomial::from_coefficients_vec(vec![Fr::from(1)]);
+        assert_ne!(challenge(&verifying_key), challenge(&shifted));
+    }
+}
@@ -1,11 +1,14 @@
-mod challenge;
 pub mod circuit;
 pub mod common_preprocessed_input;
 pub mod compiled_circuit;
 pub mod constraint;
 pub mod gate;
+pub mod hash;
+pub mod key;
 pub mod parser;
 pub mod prover;
 mod slice_polynomial;
+pub mod transcript;
 pub mod types;
 pub mod verifier;
+pub mod witness;
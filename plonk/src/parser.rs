@@ -1,9 +1,8 @@
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
-use std::ops::Neg;
 
 use ark_bls12_381::Fr;
-use ark_ff::Zero;
+use ark_ff::PrimeField;
 
 use crate::circuit::Circuit;
 use crate::parser::TypeOfCircuit::*;
@@ -13,6 +12,7 @@ enum TypeOfCircuit {
     Addition,
     Multiplication,
     Constant,
+    PublicInput,
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
@@ -23,7 +23,7 @@ struct Gate {
     pub right: Wire,
     //Bottom part (result) of the circuit
     pub bottom: Wire,
-    // type 0: add, type 1: mul, type 2: const
+    // type 0: add, type 1: mul, type 2: const, type 3: public input
     pub type_of_circuit: TypeOfCircuit,
 }
 
@@ -36,25 +36,19 @@ impl Gate {
             type_of_circuit,
         }
     }
-
-    /// Change the result value of this gate
-    pub fn change_result(&mut self, value: Fr) {
-        self.bottom.value_fr = value;
-    }
 }
 
+/// Positions `(wire, gate)` of the wires carrying each value of the equation
+type PositionMap = HashMap<String, Vec<(usize, usize)>>;
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 struct Wire {
     value_string: String,
-    value_fr: Fr,
 }
 
 impl Wire {
-    fn new(value_string: String, value_fr: Fr) -> Self {
-        Wire {
-            value_string,
-            value_fr,
-        }
+    fn new(value_string: String) -> Self {
+        Wire { value_string }
     }
 }
 
@@ -62,11 +56,12 @@ impl Wire {
 ///
 /// See parse function for usage
 #[derive(Default)]
-pub struct Parser {
-    pub witnesses: HashMap<String, Fr>,
+pub struct Parser<F: PrimeField = Fr> {
+    pub witnesses: HashMap<String, F>,
+    pub public_inputs: Vec<String>,
 }
 
-impl Parser {
+impl<F: PrimeField> Parser<F> {
     /// Add witness for the polynomial string
     ///
     /// ```
@@ -78,27 +73,87 @@ impl Parser {
     ///
     /// parser.parse("x=1");
     /// ```
-    pub fn add_witness(&mut self, variable: &str, value: Fr) {
+    pub fn add_witness(&mut self, variable: &str, value: F) {
         self.witnesses.insert(variable.to_string(), value);
     }
 
+    /// Declare a variable of the equation as a public input
+    ///
+    /// Its value is still given with [`Parser::add_witness`], but it is not part of the
+    /// circuit: the prover and the verifier get it from `Circuit::public_inputs`, in the
+    /// order the public inputs were declared.
+    ///
+    /// ```
+    /// use ark_bls12_381::Fr;
+    /// use plonk::parser::Parser;
+    ///
+    /// let mut parser = Parser::default();
+    /// parser.add_witness("x", Fr::from(3));
+    /// parser.add_witness("y", Fr::from(35));
+    /// parser.add_public_input("y");
+    ///
+    /// let circuit = parser.parse("x^3+x+5=y");
+    /// assert_eq!(circuit.public_inputs(), vec![Fr::from(35)]);
+    /// ```
+    pub fn add_public_input(&mut self, variable: &str) {
+        self.public_inputs.push(variable.to_string());
+    }
+
     /// Parse the input string to generate the circuit
-    pub fn parse(&self, input: &str) -> Circuit {
+    ///
+    /// The gates only depend on the equation and the public inputs, the witnesses only give
+    /// the values of the wires, so every parse of an equation compiles to the same circuit.
+    pub fn parse(&self, input: &str) -> Circuit<F> {
+        let input = Self::parse_string(input);
+        let (gate_list, position_map) = Self::prepare_gen_circuit(&input, &self.public_inputs);
+        Self::gen_circuit(Some(&self.witnesses), gate_list, position_map)
+    }
+
+    /// Parse the input string to generate the structure of the circuit
+    ///
+    /// The witnesses are not used, all the wires of the circuit are zeros. It compiles to
+    /// the same circuit as [`Parser::parse`], so the verifier can preprocess the circuit
+    /// from the equation and the public input declarations alone.
+    ///
+    /// ```
+    /// use ark_bls12_381::Fr;
+    /// use plonk::parser::Parser;
+    ///
+    /// let mut parser = Parser::default();
+    /// parser.add_witness("x", Fr::from(1));
+    ///
+    /// assert_eq!(
+    ///     parser.parse("x=1").compile(),
+    ///     Parser::<Fr>::default().parse_structure("x=1").compile()
+    /// );
+    /// ```
+    pub fn parse_structure(&self, input: &str) -> Circuit<F> {
         let input = Self::parse_string(input);
-        let (gate_list, position_map) = self.prepare_gen_circuit(&input);
-        Self::gen_circuit(gate_list, position_map)
+        let (gate_list, position_map) = Self::prepare_gen_circuit(&input, &self.public_inputs);
+        Self::gen_circuit(None, gate_list, position_map)
     }
 
     /// Generate [gate_list] and [position_map] to prepare for coordinate pair accumulator
-    fn prepare_gen_circuit(
-        &self,
-        string: &str,
-    ) -> (Vec<Gate>, HashMap<String, Vec<(usize, usize)>>) {
+    ///
+    /// The public input gates come first, so that their rows do not depend on the equation.
+    fn prepare_gen_circuit(string: &str, public_inputs: &[String]) -> (Vec<Gate>, PositionMap) {
         let gate_list: RefCell<Vec<Gate>> = RefCell::new(Vec::new());
         let gate_set: RefCell<HashSet<Gate>> = RefCell::new(HashSet::new());
         //Map of integer key will be here, it will then be inserted into gen circuit method
-        let position_map: RefCell<HashMap<String, Vec<(usize, usize)>>> =
-            RefCell::new(HashMap::new());
+        let position_map: RefCell<PositionMap> = RefCell::new(HashMap::new());
+
+        let public_inputs = public_inputs
+            .iter()
+            .map(|variable| variable.to_lowercase())
+            .collect::<Vec<_>>();
+        for variable in &public_inputs {
+            Self::generate_public_input_gate(
+                &mut gate_list.borrow_mut(),
+                &mut gate_set.borrow_mut(),
+                &mut position_map.borrow_mut(),
+                Wire::new(variable.clone()),
+            );
+        }
 
         let result = string
             .split('=')
@@ -109,7 +164,12 @@ impl Parser {
         let result = result[1].clone();
 
         let mut split_list: Vec<String> = string.split('+').map(|s| s.to_string()).collect();
-        split_list.push("-".to_string() + result.as_str());
+        if public_inputs.contains(&result) {
+            // A public input must be the wire of its public input gate, not its negation
+            split_list.push("-1*".to_string() + result.as_str());
+        } else {
+            split_list.push("-".to_string() + result.as_str());
+        }
         split_list
             .into_iter()
             .map(|split_list| {
@@ -117,15 +177,13 @@ impl Parser {
                     .split('*')
                     .map(|s| s.trim().to_string())
                     .map(|s| {
-                        Wire::new(
-                            s.clone(),
-                            self.get_witness_value(
-                                &s,
-                                &mut gate_list.borrow_mut(),
-                                &mut gate_set.borrow_mut(),
-                                &mut position_map.borrow_mut(),
-                            ),
-                        )
+                        Self::generate_constant_gate_if_constant(
+                            &s,
+                            &mut gate_list.borrow_mut(),
+                            &mut gate_set.borrow_mut(),
+                            &mut position_map.borrow_mut(),
+                        );
+                        Wire::new(s)
                     })
                     .collect::<Vec<Wire>>()
             })
@@ -137,10 +195,8 @@ impl Parser {
                     .into_iter()
                     .reduce(|left, right| {
                         let gate_number = gate_list.len();
-                        let result = Wire::new(
-                            format!("{}*{}", &left.value_string, &right.value_string),
-                            left.value_fr * right.value_fr,
-                        );
+                        let result =
+                            Wire::new(format!("{}*{}", &left.value_string, &right.value_string));
                         let gate =
                             Gate::new(left.clone(), right.clone(), result.clone(), Multiplication);
                         if gate_set.get(&gate).is_some() {
@@ -175,7 +231,7 @@ impl Parser {
                 let mut gate_list = gate_list.borrow_mut();
                 let mut gate_set = gate_set.borrow_mut();
                 let mut position_map = position_map.borrow_mut();
-                self.generate_additional_gate(
+                Self::generate_additional_gate(
                     &mut gate_list,
                     &mut gate_set,
                     &mut position_map,
@@ -184,20 +240,18 @@ impl Parser {
                 )
             });
 
-        gate_list
-            .borrow_mut()
-            .last_mut()
-            .unwrap()
-            .change_result(Fr::from(0));
-
         (gate_list.take(), position_map.take())
     }
 
     /// Generate the actual circuit with coordinate pair accumulator
+    ///
+    /// The values of the wires are computed gate by gate from the witnesses, or are all
+    /// zeros without witnesses.
     fn gen_circuit(
+        witnesses: Option<&HashMap<String, F>>,
         gate_list: Vec<Gate>,
-        position_map: HashMap<String, Vec<(usize, usize)>>,
-    ) -> Circuit {
+        position_map: PositionMap,
+    ) -> Circuit<F> {
         let mut result = Circuit::default();
         let mut position_map = position_map
             .into_iter()
@@ -206,37 +260,64 @@ impl Parser {
                 vec.rotate_right(1);
                 (key, vec)
             })
-            .collect::<HashMap<String, Vec<(usize, usize)>>>();
-        for gate in gate_list.iter() {
+            .collect::<PositionMap>();
+        let mut values: HashMap<String, F> = HashMap::new();
+        for (index, gate) in gate_list.iter().enumerate() {
             #[cfg(test)]
             println!("{:?}", gate);
+            let (left_value, right_value) = match witnesses {
+                Some(witnesses) => (
+                    Self::get_wire_value(witnesses, &gate.left, &values),
+                    Self::get_wire_value(witnesses, &gate.right, &values),
+                ),
+                None => (F::zero(), F::zero()),
+            };
+            let bottom_value = if witnesses.is_none()
+                || index == gate_list.len() - 1
+                || gate.type_of_circuit == PublicInput
+            {
+                // The last gate subtracts the result of the equation, so its output is zero,
+                // and the other wires of a public input gate are zeros
+                F::zero()
+            } else if gate.type_of_circuit == Multiplication {
+                left_value * right_value
+            } else {
+                left_value + right_value
+            };
+            values.insert(gate.bottom.value_string.clone(), bottom_value);
+
             let left = position_map
                 .get_mut(&gate.left.value_string)
                 .unwrap()
                 .pop()
                 .unwrap();
-            let left = (left.0, left.1, Fr::from(gate.left.value_fr));
+            let left = (left.0, left.1, left_value);
             let right = position_map
                 .get_mut(&gate.right.value_string)
                 .unwrap()
                 .pop()
                 .unwrap();
-            let right = (right.0, right.1, Fr::from(gate.right.value_fr));
+            let right = (right.0, right.1, right_value);
             let bottom = position_map
                 .get_mut(&gate.bottom.value_string)
                 .unwrap()
                 .pop()
                 .unwrap();
-            let bottom = (bottom.0, bottom.1, Fr::from(gate.bottom.value_fr));
+            let bottom = (bottom.0, bottom.1, bottom_value);
             match gate.type_of_circuit {
                 Addition => {
-                    result.add_addition_gate(left, right, bottom, Fr::from(0));
+                    result.add_addition_gate(left, right, bottom, F::zero());
                 }
                 Multiplication => {
-                    result.add_multiplication_gate(left, right, bottom, Fr::from(0));
+                    result.add_multiplication_gate(left, right, bottom, F::zero());
                 }
                 Constant => {
-                    result.add_constant_gate(left, right, bottom, Fr::from(0));
+                    let constant = Self::get_constant_value(&gate.left.value_string)
+                        .expect("The left wire of a constant gate is a constant");
+                    result.add_constant_gate(left, right, bottom, constant, F::zero());
+                }
+                PublicInput => {
+                    result.add_public_input_gate(left, right, bottom);
                 }
             }
             #[cfg(test)]
@@ -247,18 +328,14 @@ impl Parser {
 
     /// Generate an additional gate
     fn generate_additional_gate(
-        &self,
         gate_list: &mut Vec<Gate>,
         gate_set: &mut HashSet<Gate>,
-        position_map: &mut HashMap<String, Vec<(usize, usize)>>,
+        position_map: &mut PositionMap,
         left: Wire,
         right: Wire,
     ) -> Wire {
         let gate_number = gate_list.len();
-        let result = Wire::new(
-            format!("{}+{}", &left.value_string, &right.value_string),
-            left.value_fr + right.value_fr,
-        );
+        let result = Wire::new(format!("{}+{}", &left.value_string, &right.value_string));
         let gate = Gate::new(left.clone(), right.clone(), result.clone(), Addition);
         //if this gate already exist, skip this move
         if gate_set.get(&gate).is_some() {
@@ -278,18 +355,14 @@ impl Parser {
     /// Take in `value` to make constant gate.
     /// Constant gate ensure the prover send the correct polynomial
     fn generate_constant_gate(
-        &self,
         gate_list: &mut Vec<Gate>,
         gate_set: &mut HashSet<Gate>,
-        position_map: &mut HashMap<String, Vec<(usize, usize)>>,
+        position_map: &mut PositionMap,
         value: Wire,
     ) -> Wire {
         let gate_number = gate_list.len();
-        let right = Wire::new("0".to_string(), Fr::zero());
-        let result = Wire::new(
-            format!("{}+{}", &value.value_string, "0"),
-            value.value_fr + Fr::zero(),
-        );
+        let right = Wire::new("0".to_string());
+        let result = Wire::new(format!("{}+{}", &value.value_string, "0"));
         let gate = Gate::new(value.clone(), right.clone(), result.clone(), Constant);
         //if this gate already exist, skip this move
         if gate_set.get(&gate).is_some() {
@@ -304,45 +377,92 @@ impl Parser {
         result
     }
 
-    /// Get the value of `value` in Fr
-    /// if value is a constant insert a constant gate
-    fn get_witness_value(
-        &self,
-        mut value: &str,
+    /// Generate a public input gate
+    ///
+    /// Take in the variable `value` to expose, the other wires of the gate are zeros.
+    fn generate_public_input_gate(
         gate_list: &mut Vec<Gate>,
         gate_set: &mut HashSet<Gate>,
-        position_map: &mut HashMap<String, Vec<(usize, usize)>>,
-    ) -> Fr {
-        let mut is_negative = false;
-        if &value[..1] == "-" {
-            is_negative = true;
-            value = &value[1..];
+        position_map: &mut PositionMap,
+        value: Wire,
+    ) {
+        let gate_number = gate_list.len();
+        let zero = Wire::new("0".to_string());
+        let gate = Gate::new(value.clone(), zero.clone(), zero, PublicInput);
+        //if this gate already exist, skip this move
+        if gate_set.get(&gate).is_some() {
+            return;
         }
-        let result = match self.witnesses.get(value) {
-            //Not a constant, search in map
-            Some(value) => *value,
-            //Value is a constant insert a constant gate
-            None => {
-                eprintln!("value = {:#?}", value);
-                let constant = value.parse::<i32>().unwrap();
-                let wire = if is_negative {
-                    Wire::new(
-                        "-".to_string() + constant.to_string().as_str(),
-                        Fr::from(constant).neg(),
-                    )
-                } else {
-                    Wire::new(constant.to_string(), Fr::from(constant))
-                };
-                #[cfg(test)]
-                println!("{:?} {}", wire, is_negative);
-                self.generate_constant_gate(gate_list, gate_set, position_map, wire.clone());
-                Fr::from(constant)
-            }
+        gate_list.push(gate.clone());
+        gate_set.insert(gate);
+
+        Self::push_into_position_map_or_insert(0, gate_number, position_map, &value.value_string);
+        Self::push_into_position_map_or_insert(1, gate_number, position_map, "0");
+        Self::push_into_position_map_or_insert(2, gate_number, position_map, "0");
+    }
+
+    /// Insert a constant gate if `value` is a constant rather than a variable
+    fn generate_constant_gate_if_constant(
+        value: &str,
+        gate_list: &mut Vec<Gate>,
+        gate_set: &mut HashSet<Gate>,
+        position_map: &mut PositionMap,
+    ) {
+        let (is_negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        if let Ok(constant) = value.parse::<i32>() {
+            let wire = if is_negative {
+                Wire::new("-".to_string() + constant.to_string().as_str())
+            } else {
+                Wire::new(constant.to_string())
+            };
+            #[cfg(test)]
+            println!("{:?} {}", wire, is_negative);
+            Self::generate_constant_gate(gate_list, gate_set, position_map, wire);
+        }
+    }
+
+    /// Get the value of a constant in the scalar field, or `None` for a variable
+    fn get_constant_value(value: &str) -> Option<F> {
+        let (is_negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let constant = constant_to_field::<F>(value.parse::<i32>().ok()?);
+        Some(if is_negative {
+            constant.neg()
+        } else {
+            constant
+        })
+    }
+
+    /// Get the value of `wire` in the scalar field
+    ///
+    /// A wire is the output of a previous gate, a witness or a constant.
+    fn get_wire_value(
+        witnesses: &HashMap<String, F>,
+        wire: &Wire,
+        values: &HashMap<String, F>,
+    ) -> F {
+        if let Some(value) = values.get(&wire.value_string) {
+            return *value;
+        }
+        if let Some(constant) = Self::get_constant_value(&wire.value_string) {
+            return constant;
+        }
+        let (is_negative, variable) = match wire.value_string.strip_prefix('-') {
+            Some(variable) => (true, variable),
+            None => (false, wire.value_string.as_str()),
         };
+        let value = *witnesses
+            .get(variable)
+            .unwrap_or_else(|| panic!("Missing witness for {}", variable));
         if is_negative {
-            result.neg()
+            value.neg()
         } else {
-            result
+            value
         }
     }
 
@@ -350,7 +470,7 @@ impl Parser {
     fn push_into_position_map_or_insert(
         wire_number: usize,
         gate_number: usize,
-        position_map: &mut HashMap<String, Vec<(usize, usize)>>,
+        position_map: &mut PositionMap,
         value: &str,
     ) {
         let var_exist = position_map.get(value).is_some();
@@ -417,6 +537,16 @@ impl Parser {
 
 //TODO: implement / operator
 
+/// Converts a signed integer constant of an equation to a field element.
+pub(crate) fn constant_to_field<F: PrimeField>(constant: i32) -> F {
+    let value = F::from(constant.unsigned_abs());
+    if constant < 0 {
+        -value
+    } else {
+        value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // use ark_bls12_381::Fr;
@@ -1,66 +1,147 @@
-use std::ops::{Add, Div, Mul};
+use std::ops::Mul;
 
-use ark_bls12_381::Fr;
-use ark_ff::{Field, UniformRand, Zero};
-use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial};
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_ff::{batch_inversion, PrimeField, UniformRand};
+use ark_poly::univariate::DensePolynomial;
 use ark_poly::{
     DenseUVPolynomial, EvaluationDomain, Evaluations, GeneralEvaluationDomain, Polynomial as Poly,
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::rngs::StdRng;
 use ark_std::rand::SeedableRng;
+use ark_std::{cfg_into_iter, cfg_iter};
 use digest::Digest;
-use sha2::Sha256;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use kzg::commitment::KzgCommitment;
+use kzg::error::KzgError;
+use kzg::msm::msm;
 use kzg::scheme::KzgScheme;
 use kzg::srs::Srs;
 
-use crate::challenge::ChallengeGenerator;
 use crate::compiled_circuit::CompiledCircuit;
+use crate::key::{quotient_domain, CosetEvaluations, ProvingKey, VerifyingKey};
 use crate::slice_polynomial::SlicePoly;
+use crate::transcript::Transcript;
 use crate::types::Polynomial;
+use crate::witness::Witness;
 
 /// Struct representing a proof.
-#[derive(CanonicalDeserialize, CanonicalSerialize)]
-pub struct Proof {
-    pub a_commit: KzgCommitment,
+#[derive(Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
+pub struct Proof<E: Pairing = Bls12_381> {
+    pub a_commit: KzgCommitment<E>,
     /// Commitment of wire polynomial b(x)
-    pub b_commit: KzgCommitment,
+    pub b_commit: KzgCommitment<E>,
     /// Commitment of wire polynomial c(x)
-    pub c_commit: KzgCommitment,
+    pub c_commit: KzgCommitment<E>,
     /// Commitment of permutation polynomial z(x)
-    pub z_commit: KzgCommitment,
+    pub z_commit: KzgCommitment<E>,
     /// Commitment of the first part of quotient polynomial t(X)
-    pub t_lo_commit: KzgCommitment,
+    pub t_lo_commit: KzgCommitment<E>,
     /// Commitment of the second part of quotient polynomial t(X)
-    pub t_mid_commit: KzgCommitment,
+    pub t_mid_commit: KzgCommitment<E>,
     /// Commitment of the third part of quotient polynomial t(X)
-    pub t_hi_commit: KzgCommitment,
-    /// Commitment of opening proof polynomial w_ev_x
-    pub w_ev_x_commit: KzgCommitment,
-    /// Commitment of opening proof polynomial w_ev_wx
-    pub w_ev_wx_commit: KzgCommitment,
+    pub t_hi_commit: KzgCommitment<E>,
+    /// Commitment of opening proof polynomial w_ev_x, or the witness W with SHPLONK
+    pub w_ev_x_commit: KzgCommitment<E>,
+    /// Commitment of opening proof polynomial w_ev_wx, or the witness W' with SHPLONK
+    pub w_ev_wx_commit: KzgCommitment<E>,
     /// Opening evaluation of a(x)
-    pub bar_a: Fr,
+    pub bar_a: E::ScalarField,
     /// Opening evaluation of b(x)
-    pub bar_b: Fr,
+    pub bar_b: E::ScalarField,
     /// Opening evaluation of c(x)
-    pub bar_c: Fr,
+    pub bar_c: E::ScalarField,
     /// Opening evaluation of s_sigma_1(x)
-    pub bar_s_sigma_1: Fr,
+    pub bar_s_sigma_1: E::ScalarField,
     /// Opening evaluation of s_sigma_2(x)
-    pub bar_s_sigma_2: Fr,
+    pub bar_s_sigma_2: E::ScalarField,
     /// Opening evaluation of z_w(x)
-    pub bar_z_w: Fr,
-    /// Multipoint evaluation challenge
-    pub u: Fr,
-    /// Degree of each part of quotient polynomial
-    pub degree: usize,
+    pub bar_z_w: E::ScalarField,
 }
 
-/// Generates a proof for the compiled circuit.
-pub fn generate_proof<T: Digest + Default>(compiled_circuit: &CompiledCircuit, srs: Srs) -> Proof {
+/// Label of the protocol, absorbed first in the transcript of each proof.
+pub(crate) const TRANSCRIPT_LABEL: &[u8] = b"plonk";
+
+/// Scheme used to open the polynomials at the evaluation challenge in round 5.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OpeningScheme {
+    /// Two KZG witnesses, one for the openings at ζ and one for the opening at ζω.
+    #[default]
+    Kzg,
+    /// A single SHPLONK multi-point opening of all polynomials at ζ and ζω.
+    ///
+    /// Host only: the proof carries two points either way, so it is no smaller, and the
+    /// generated verifier contract only checks [`OpeningScheme::Kzg`] proofs.
+    Shplonk,
+}
+
+/// Generates a proof for the compiled circuit with the given witness and public inputs.
+///
+/// The public inputs are the values of the public input gates, in the order they were
+/// added. Fails if the SRS is too small for the circuit.
+pub fn generate_proof<T: Digest + Default, E: Pairing>(
+    compiled_circuit: &CompiledCircuit<E::ScalarField>,
+    witness: &Witness<E::ScalarField>,
+    public_inputs: &[E::ScalarField],
+    srs: Srs<E>,
+) -> Result<Proof<E>, String> {
+    generate_proof_with_opening::<T, E>(
+        compiled_circuit,
+        witness,
+        public_inputs,
+        srs,
+        OpeningScheme::Kzg,
+    )
+}
+
+/// Generates a proof for the compiled circuit using the given opening scheme.
+pub fn generate_proof_with_opening<T: Digest + Default, E: Pairing>(
+    compiled_circuit: &CompiledCircuit<E::ScalarField>,
+    witness: &Witness<E::ScalarField>,
+    public_inputs: &[E::ScalarField],
+    srs: Srs<E>,
+    opening: OpeningScheme,
+) -> Result<Proof<E>, String> {
+    generate_proof_with_scheme::<T, E>(
+        compiled_circuit,
+        witness,
+        public_inputs,
+        KzgScheme::new(srs),
+        opening,
+    )
+}
+
+/// Generates a proof for the compiled circuit, committing with the given KZG scheme.
+///
+/// This allows proving with a scheme that does not hold the SRS in memory, such as
+/// one created with `KzgScheme::new_mapped`.
+pub fn generate_proof_with_scheme<T: Digest + Default, E: Pairing>(
+    compiled_circuit: &CompiledCircuit<E::ScalarField>,
+    witness: &Witness<E::ScalarField>,
+    public_inputs: &[E::ScalarField],
+    scheme: KzgScheme<E>,
+    opening: OpeningScheme,
+) -> Result<Proof<E>, String> {
+    let verifying_key = VerifyingKey::with_scheme(compiled_circuit, &scheme)?;
+    let proving_key = ProvingKey::new(compiled_circuit, verifying_key, &scheme)?;
+    generate_proof_with_key::<T, E>(&proving_key, witness, public_inputs, scheme, opening)
+}
+
+/// Generates a proof for a witness of the circuit of a proving key from `setup`.
+pub fn generate_proof_with_key<T: Digest + Default, E: Pairing>(
+    proving_key: &ProvingKey<E>,
+    witness: &Witness<E::ScalarField>,
+    public_inputs: &[E::ScalarField],
+    scheme: KzgScheme<E>,
+    opening: OpeningScheme,
+) -> Result<Proof<E>, String> {
+    let wire_evals = witness.evaluations(proving_key.n)?;
+    let pi_x = proving_key.public_input_poly(public_inputs)?;
+
     println!("Generating proof...");
 
     // Round 1
@@ -68,72 +149,74 @@ pub fn generate_proof<T: Digest + Default>(compiled_circuit: &CompiledCircuit, s
     println!("ROUND 1");
 
     let mut rng = StdRng::from_entropy();
-    let scheme = KzgScheme::new(srs);
-    let domain = <GeneralEvaluationDomain<Fr>>::new(compiled_circuit.size).unwrap();
+    let domain = <GeneralEvaluationDomain<E::ScalarField>>::new(proving_key.n).unwrap();
 
-    let b1 = Fr::rand(&mut rng);
-    let b2 = Fr::rand(&mut rng);
-    let b3 = Fr::rand(&mut rng);
-    let b4 = Fr::rand(&mut rng);
-    let b5 = Fr::rand(&mut rng);
-    let b6 = Fr::rand(&mut rng);
+    let b1 = E::ScalarField::rand(&mut rng);
+    let b2 = E::ScalarField::rand(&mut rng);
+    let b3 = E::ScalarField::rand(&mut rng);
+    let b4 = E::ScalarField::rand(&mut rng);
+    let b5 = E::ScalarField::rand(&mut rng);
+    let b6 = E::ScalarField::rand(&mut rng);
 
     let pre1 = DensePolynomial::from_coefficients_vec(vec![b2, b1]);
     let pre2 = DensePolynomial::from_coefficients_vec(vec![b4, b3]);
     let pre3 = DensePolynomial::from_coefficients_vec(vec![b6, b5]);
 
-    let ax = pre1.mul_by_vanishing_poly(domain);
-    let ax = compiled_circuit.gate_constraints().f_ax().clone().add(ax);
-
-    let bx = pre2.mul_by_vanishing_poly(domain);
-    let bx = compiled_circuit.gate_constraints().f_bx().clone().add(bx);
-
-    let cx = pre3.mul_by_vanishing_poly(domain);
-    let cx = compiled_circuit.gate_constraints().f_cx().clone().add(cx);
+    let blind_a = pre1.mul_by_vanishing_poly(domain);
+    let blind_b = pre2.mul_by_vanishing_poly(domain);
+    let blind_c = pre3.mul_by_vanishing_poly(domain);
 
-    let [a_commit, b_commit, c_commit] = commit_round1(&ax, &bx, &cx, &scheme);
+    let [a_commit, b_commit, c_commit] = commit_round1(
+        &wire_evals,
+        [&blind_a, &blind_b, &blind_c],
+        &proving_key.lagrange_basis,
+        &scheme,
+    )?;
 
     // Round 2
     #[cfg(test)]
     println!("ROUND 2");
 
-    let mut challenge = ChallengeGenerator::<Sha256>::default();
-    challenge.feed(&a_commit);
-    challenge.feed(&b_commit);
-    challenge.feed(&c_commit);
-    let [beta, gamma] = challenge.generate_challenges();
+    let mut transcript = Transcript::<T>::new(TRANSCRIPT_LABEL);
+    proving_key
+        .verifying_key
+        .append_to_transcript(&mut transcript);
+    for public_input in public_inputs {
+        transcript.append_scalar(b"pi", public_input);
+    }
+    transcript.append_point(b"a", a_commit.inner());
+    transcript.append_point(b"b", b_commit.inner());
+    transcript.append_point(b"c", c_commit.inner());
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
 
-    let b7 = Fr::rand(&mut rng);
-    let b8 = Fr::rand(&mut rng);
-    let b9 = Fr::rand(&mut rng);
+    let b7 = E::ScalarField::rand(&mut rng);
+    let b8 = E::ScalarField::rand(&mut rng);
+    let b9 = E::ScalarField::rand(&mut rng);
 
     let pre4 = DensePolynomial::from_coefficients_vec(vec![b9, b8, b7]);
     let pre4 = pre4.mul_by_vanishing_poly(domain);
 
     let w = domain.element(1);
-    let pre4w = DensePolynomial::from_coefficients_vec(vec![b9, b8 * w, b7 * domain.element(2)]);
-    let pre4w = pre4w.mul_by_vanishing_poly(domain);
-
-    let (acc_x, acc_wx) = compute_acc(&beta, &gamma, &domain, compiled_circuit);
-    // check z_x and z_wx
-    #[cfg(test)]
-    assert_eq!(acc_x.evaluate(&(w * beta)), acc_wx.evaluate(&beta));
-
+    let acc_x = compute_acc(&beta, &gamma, &domain, proving_key, &wire_evals);
     let z_x = pre4 + acc_x;
-    let z_wx = pre4w + acc_wx;
 
-    let z_commit = scheme.commit(&z_x);
-
-    // check z_x and z_wx
-    #[cfg(test)]
-    assert_eq!(z_x.evaluate(&(w * beta)), z_wx.evaluate(&beta));
+    let z_commit = scheme.commit(&z_x)?;
 
     // Round 3
     #[cfg(test)]
     println!("ROUND 3");
 
-    challenge.feed(&z_commit);
-    let [alpha] = challenge.generate_challenges();
+    transcript.append_point(b"z", z_commit.inner());
+    let alpha = transcript.challenge_scalar(b"alpha");
+
+    // The wires are only interpolated now, for the quotient and the openings.
+    let [ax, bx, cx] = wire_evals
+        .clone()
+        .map(|evals| Evaluations::from_vec_and_domain(evals, domain).interpolate());
+    let ax = &ax + &blind_a;
+    let bx = &bx + &blind_b;
+    let cx = &cx + &blind_c;
 
     let tx = compute_quotient_polynomial(
         &beta,
@@ -143,53 +226,45 @@ pub fn generate_proof<T: Digest + Default>(compiled_circuit: &CompiledCircuit, s
         &bx,
         &cx,
         &z_x,
-        &z_wx,
+        &pi_x,
         &domain,
-        compiled_circuit,
+        proving_key,
     );
 
-    let slice_poly = SlicePoly::new(tx);
-    let [t_lo_commit, t_mid_commit, t_hi_commit] = slice_poly.commit(&scheme);
+    let b10 = E::ScalarField::rand(&mut rng);
+    let b11 = E::ScalarField::rand(&mut rng);
+
+    let slice_poly = SlicePoly::new(tx, proving_key.n, [b10, b11])?;
+    let [t_lo_commit, t_mid_commit, t_hi_commit] = slice_poly.commit(&scheme)?;
 
     // Round 4
     #[cfg(test)]
     println!("ROUND 4");
 
-    challenge.feed(&t_lo_commit.clone());
-    challenge.feed(&t_mid_commit.clone());
-    challenge.feed(&t_hi_commit.clone());
-
-    let [evaluation_challenge] = challenge.generate_challenges();
+    transcript.append_point(b"t_lo", t_lo_commit.inner());
+    transcript.append_point(b"t_mid", t_mid_commit.inner());
+    transcript.append_point(b"t_hi", t_hi_commit.inner());
+    let evaluation_challenge = transcript.challenge_scalar(b"zeta");
 
     let bar_a = ax.evaluate(&evaluation_challenge);
     let bar_b = bx.evaluate(&evaluation_challenge);
     let bar_c = cx.evaluate(&evaluation_challenge);
-    let bar_s_sigma_1 = compiled_circuit
-        .copy_constraints()
-        .s_sigma_1()
-        .evaluate(&evaluation_challenge);
-    let bar_s_sigma_2 = compiled_circuit
-        .copy_constraints()
-        .s_sigma_2()
-        .evaluate(&evaluation_challenge);
+    let bar_s_sigma_1 = proving_key.s_sigma_1.evaluate(&evaluation_challenge);
+    let bar_s_sigma_2 = proving_key.s_sigma_2.evaluate(&evaluation_challenge);
     let bar_z_w = z_x.evaluate(&(evaluation_challenge * w));
-    let pi_e = compiled_circuit
-        .gate_constraints()
-        .pi_x()
-        .evaluate(&evaluation_challenge);
+    let pi_e = pi_x.evaluate(&evaluation_challenge);
     let tx_compact = slice_poly.compact(&evaluation_challenge);
 
     // Round 5
     #[cfg(test)]
     println!("ROUND 5");
-    challenge.feed(&scheme.commit_para(bar_a));
-    challenge.feed(&scheme.commit_para(bar_b));
-    challenge.feed(&scheme.commit_para(bar_c));
-    challenge.feed(&scheme.commit_para(bar_s_sigma_1));
-    challenge.feed(&scheme.commit_para(bar_s_sigma_2));
-    challenge.feed(&scheme.commit_para(bar_z_w));
-
-    let [v] = challenge.generate_challenges();
+    transcript.append_scalar(b"bar_a", &bar_a);
+    transcript.append_scalar(b"bar_b", &bar_b);
+    transcript.append_scalar(b"bar_c", &bar_c);
+    transcript.append_scalar(b"bar_s_sigma_1", &bar_s_sigma_1);
+    transcript.append_scalar(b"bar_s_sigma_2", &bar_s_sigma_2);
+    transcript.append_scalar(b"bar_z_w", &bar_z_w);
+    let v = transcript.challenge_scalar(b"v");
     let r_x = compute_linearisation_polynomial(
         &beta,
         &gamma,
@@ -204,76 +279,47 @@ pub fn generate_proof<T: Digest + Default>(compiled_circuit: &CompiledCircuit, s
         &pi_e,
         &tx_compact,
         &z_x,
-        &ax,
-        &bx,
-        &cx,
-        &z_wx,
         &domain,
-        compiled_circuit,
+        proving_key,
     );
-    let bar_r = r_x.evaluate(&evaluation_challenge);
-
-    let w_ev_x = poly_sub_para(&r_x, &bar_r)
-        + poly_sub_para(&ax, &bar_a).mul(v)
-        + poly_sub_para(&bx, &bar_b).mul(v.square())
-        + poly_sub_para(&cx, &bar_c).mul(v * v * v)
-        + poly_sub_para(
-            compiled_circuit.copy_constraints().s_sigma_1(),
-            &bar_s_sigma_1,
-        )
-        .mul(v * v * v * v)
-        + poly_sub_para(
-            compiled_circuit.copy_constraints().s_sigma_2(),
-            &bar_s_sigma_2,
-        )
-        .mul(v * v * v * v * v);
-
-    // Check w_ev_x
-    {
-        let cur = DensePolynomial::from_coefficients_vec(vec![-evaluation_challenge, Fr::from(1)]);
-        let a = DenseOrSparsePolynomial::from(w_ev_x.clone());
-        let b = DenseOrSparsePolynomial::from(cur);
-        let div = a.divide_with_q_and_r(&b).expect("division failed");
-        assert_eq!(
-            div.1,
-            DensePolynomial::from_coefficients_vec(vec![]),
-            "w_ev_x was computed incorrectly"
-        );
-    }
-
-    let w_ev_x = w_ev_x.div(&DensePolynomial::from_coefficients_vec(vec![
-        -evaluation_challenge,
-        Fr::from(1),
-    ]));
-    let w_ev_wx = poly_sub_para(&z_x, &bar_z_w);
-
-    // Check w_ev_wx
-    {
-        let cur =
-            DensePolynomial::from_coefficients_vec(vec![-evaluation_challenge * w, Fr::from(1)]);
-        let a = DenseOrSparsePolynomial::from(w_ev_wx.clone());
-        let b = DenseOrSparsePolynomial::from(cur);
-        let div = a.divide_with_q_and_r(&b).expect("division failed");
-        assert_eq!(
-            div.1,
-            DensePolynomial::from_coefficients_vec(vec![]),
-            "w_ev_wx was computed incorrectly"
-        );
-    }
-
-    let w_ev_wx = w_ev_wx.div(&DensePolynomial::from_coefficients_vec(vec![
-        -evaluation_challenge * w,
-        Fr::from(1),
-    ]));
-
-    let w_ev_x_commit = scheme.commit(&w_ev_x);
-    let w_ev_wx_commit = scheme.commit(&w_ev_wx);
-
-    challenge.feed(&w_ev_x_commit);
-    challenge.feed(&w_ev_wx_commit);
-    let [u] = challenge.generate_challenges();
+    let s_sigma_1 = proving_key.s_sigma_1.clone();
+    let s_sigma_2 = proving_key.s_sigma_2.clone();
+    let (w_ev_x_commit, w_ev_wx_commit) = match opening {
+        OpeningScheme::Kzg => {
+            let open_x = || {
+                scheme.batch_open(
+                    &[r_x, ax, bx, cx, s_sigma_1, s_sigma_2],
+                    evaluation_challenge,
+                    v,
+                )
+            };
+            let open_wx = || scheme.open(z_x, evaluation_challenge * w);
+            #[cfg(feature = "parallel")]
+            let (w_ev_x, w_ev_wx) = rayon::join(open_x, open_wx);
+            #[cfg(not(feature = "parallel"))]
+            let (w_ev_x, w_ev_wx) = (open_x(), open_wx());
+            let (w_ev_x, w_ev_wx) = (w_ev_x?, w_ev_wx?);
+
+            (KzgCommitment(w_ev_x.0), KzgCommitment(w_ev_wx.0))
+        }
+        OpeningScheme::Shplonk => {
+            let multi_opening = scheme.multi_open(
+                &[r_x, ax, bx, cx, s_sigma_1, s_sigma_2, z_x],
+                &shplonk_points(evaluation_challenge, w),
+                v,
+                |w_commit| {
+                    transcript.append_point(b"w_ev_x", w_commit.inner());
+                    transcript.challenge_scalar(b"u")
+                },
+            )?;
+            (
+                KzgCommitment(multi_opening.0),
+                KzgCommitment(multi_opening.1),
+            )
+        }
+    };
 
-    Proof {
+    Ok(Proof {
         a_commit,
         b_commit,
         c_commit,
@@ -289,219 +335,177 @@ pub fn generate_proof<T: Digest + Default>(compiled_circuit: &CompiledCircuit, s
         bar_s_sigma_1,
         bar_s_sigma_2,
         bar_z_w,
-        u,
-        degree: slice_poly.get_degree(),
-    }
+    })
 }
 
-/// Subtracts a parameter from a polynomial.
-fn poly_sub_para(poly: &Polynomial, para: &Fr) -> Polynomial {
-    let mut tmp = poly.clone();
-    tmp.coeffs[0] -= para;
-    tmp
+/// Returns the opening points of r(X), a(X), b(X), c(X), s_sigma_1(X), s_sigma_2(X) and z(X)
+/// used with SHPLONK.
+pub(crate) fn shplonk_points<F: PrimeField>(evaluation_challenge: F, w: F) -> Vec<Vec<F>> {
+    let mut points = vec![vec![evaluation_challenge]; 6];
+    points.push(vec![evaluation_challenge * w]);
+    points
 }
 
-fn compute_acc(
-    beta: &Fr,
-    gamma: &Fr,
-    domain: &GeneralEvaluationDomain<Fr>,
-    compiled_circuit: &CompiledCircuit,
-) -> (Polynomial, Polynomial) {
-    let mut acc_e = vec![Fr::from(1)];
-    let mut pre_acc_e = Fr::from(1);
+fn compute_acc<F: PrimeField, E: Pairing<ScalarField = F>>(
+    beta: &F,
+    gamma: &F,
+    domain: &GeneralEvaluationDomain<F>,
+    proving_key: &ProvingKey<E>,
+    wire_evals: &[Vec<F>; 3],
+) -> Polynomial<F> {
     let roots = domain.elements().collect::<Vec<_>>();
-    let k1 = compiled_circuit.copy_constraints().k1();
-    let k2 = compiled_circuit.copy_constraints().k2();
-
-    for i in 1..compiled_circuit.size {
-        let w_i_sub1 = roots.get(i - 1).unwrap();
-
-        let numerator = (compiled_circuit
-            .gate_constraints()
-            .f_ax()
-            .evaluate(w_i_sub1)
-            + *beta * w_i_sub1
-            + *gamma)
-            * (compiled_circuit
-                .gate_constraints()
-                .f_bx()
-                .evaluate(w_i_sub1)
-                + *beta * k1 * w_i_sub1
-                + *gamma)
-            * (compiled_circuit
-                .gate_constraints()
-                .f_cx()
-                .evaluate(w_i_sub1)
-                + *beta * k2 * w_i_sub1
-                + *gamma);
-
-        let denominator = (compiled_circuit
-            .gate_constraints()
-            .f_ax()
-            .evaluate(w_i_sub1)
-            + *beta
-                * compiled_circuit
-                    .copy_constraints()
-                    .s_sigma_1()
-                    .evaluate(w_i_sub1)
-            + *gamma)
-            * (compiled_circuit
-                .gate_constraints()
-                .f_bx()
-                .evaluate(w_i_sub1)
-                + *beta
-                    * compiled_circuit
-                        .copy_constraints()
-                        .s_sigma_2()
-                        .evaluate(w_i_sub1)
-                + *gamma)
-            * (compiled_circuit
-                .gate_constraints()
-                .f_cx()
-                .evaluate(w_i_sub1)
-                + *beta
-                    * compiled_circuit
-                        .copy_constraints()
-                        .s_sigma_3()
-                        .evaluate(w_i_sub1)
-                + *gamma);
-
-        pre_acc_e = pre_acc_e * numerator / denominator;
+    let k1 = proving_key.k1;
+    let k2 = proving_key.k2;
+
+    // Values of the wire and permutation polynomials over the domain.
+    let [f_a, f_b, f_c] = wire_evals;
+    let [s_sigma_1, s_sigma_2, s_sigma_3] = &proving_key.s_sigma_evals;
+
+    // The ratio of each row is independent, only the running product is sequential.
+    let (numerators, mut denominators): (Vec<F>, Vec<F>) = cfg_into_iter!(0..proving_key.n - 1)
+        .map(|i| {
+            let w_i = roots[i];
+            let numerator = (f_a[i] + *beta * w_i + gamma)
+                * (f_b[i] + *beta * k1 * w_i + gamma)
+                * (f_c[i] + *beta * k2 * w_i + gamma);
+            let denominator = (f_a[i] + *beta * s_sigma_1[i] + gamma)
+                * (f_b[i] + *beta * s_sigma_2[i] + gamma)
+                * (f_c[i] + *beta * s_sigma_3[i] + gamma);
+            (numerator, denominator)
+        })
+        .unzip();
+    batch_inversion(&mut denominators);
+
+    let mut acc_e = Vec::with_capacity(proving_key.n);
+    let mut pre_acc_e = F::one();
+    acc_e.push(pre_acc_e);
+    for (numerator, inverse) in numerators.into_iter().zip(denominators) {
+        pre_acc_e = pre_acc_e * numerator * inverse;
         acc_e.push(pre_acc_e);
     }
 
-    let mut acc_e_shifted = acc_e.clone();
-    acc_e_shifted.rotate_left(1);
-
-    let acc = Evaluations::from_vec_and_domain(acc_e, *domain).interpolate();
-    let acc_w = Evaluations::from_vec_and_domain(acc_e_shifted, *domain).interpolate();
-    (acc, acc_w)
+    Evaluations::from_vec_and_domain(acc_e, *domain).interpolate()
 }
 
-/// Computes the accumulator polynomials `acc(x)` and `acc(w*x)` for a given beta, gamma, evaluation domain, and compiled circuit.
+/// Computes the quotient polynomial t(X) for the given challenges and circuit.
+///
+/// The wire, permutation and public input polynomials are evaluated on the quotient
+/// coset, where the proving key holds the evaluations of the preprocessed polynomials.
+/// The numerator is computed pointwise, divided by the vanishing polynomial of `domain`
+/// and interpolated back with a single inverse FFT.
 #[allow(clippy::too_many_arguments)]
-fn compute_quotient_polynomial(
-    beta: &Fr,
-    gamma: &Fr,
-    alpha: &Fr,
-    ax: &Polynomial,
-    bx: &Polynomial,
-    cx: &Polynomial,
-    z_x: &Polynomial,
-    z_wx: &Polynomial,
-    domain: &GeneralEvaluationDomain<Fr>,
-    compiled_circuit: &CompiledCircuit,
-) -> Polynomial {
-    let k1 = compiled_circuit.copy_constraints().k1();
-    let k2 = compiled_circuit.copy_constraints().k2();
-
-    let line1 = &(ax * bx) * compiled_circuit.gate_constraints().q_mx()
-        + ax * compiled_circuit.gate_constraints().q_lx()
-        + bx * compiled_circuit.gate_constraints().q_rx()
-        + cx * compiled_circuit.gate_constraints().q_ox()
-        + compiled_circuit.gate_constraints().pi_x().clone()
-        + compiled_circuit.gate_constraints().q_cx().clone();
-
-    // check line 1
-    let quotient1 = divide_by_vanishing_poly(&line1, domain).expect("No remainder 1");
-
-    let line2 = (ax.clone() + DensePolynomial::from_coefficients_vec(vec![*gamma, *beta]))
-        .mul(&(bx.clone() + DensePolynomial::from_coefficients_vec(vec![*gamma, *beta * k1])))
-        .mul(&(cx.clone() + DensePolynomial::from_coefficients_vec(vec![*gamma, *beta * k2])))
-        .mul(z_x)
-        .mul(*alpha);
-
-    let line3 = (ax.clone()
-        + compiled_circuit.copy_constraints().s_sigma_1().mul(*beta)
-        + DensePolynomial::from_coefficients_vec(vec![*gamma]))
-    .mul(
-        &(bx.clone()
-            + compiled_circuit.copy_constraints().s_sigma_2().mul(*beta)
-            + DensePolynomial::from_coefficients_vec(vec![*gamma])),
-    )
-    .mul(
-        &(cx.clone()
-            + compiled_circuit.copy_constraints().s_sigma_3().mul(*beta)
-            + DensePolynomial::from_coefficients_vec(vec![*gamma])),
-    )
-    .mul(z_wx)
-    .mul(*alpha);
-
-    let line23 = &line2 - &line3;
-
-    // check line 23
-    let quotient23 = divide_by_vanishing_poly(&line23, domain).expect("No remainder here");
-
-    let line4 = {
-        let l1 = l1_poly(domain);
-        let mut zx2 = z_x.clone();
-        zx2.coeffs[0] -= Fr::from(1);
-        zx2.mul(&l1).mul(alpha.square())
-    };
-
-    // check line 4
-    let quotient4 = divide_by_vanishing_poly(&line4, domain).expect("No remainder here");
-
-    quotient1 + quotient23 + quotient4
-}
-
-fn divide_by_vanishing_poly<'a>(
-    poly: &Polynomial,
-    domain: &GeneralEvaluationDomain<Fr>,
-) -> Result<Polynomial, &'a str> {
-    let (result, rest) = poly.divide_by_vanishing_poly(*domain).unwrap();
-    if !rest.is_zero() {
-        return Err("has remainder");
-    }
-    Ok(result)
+fn compute_quotient_polynomial<F: PrimeField, E: Pairing<ScalarField = F>>(
+    beta: &F,
+    gamma: &F,
+    alpha: &F,
+    ax: &Polynomial<F>,
+    bx: &Polynomial<F>,
+    cx: &Polynomial<F>,
+    z_x: &Polynomial<F>,
+    pi_x: &Polynomial<F>,
+    domain: &GeneralEvaluationDomain<F>,
+    proving_key: &ProvingKey<E>,
+) -> Polynomial<F> {
+    let n = domain.size();
+    let k1 = proving_key.k1;
+    let k2 = proving_key.k2;
+
+    let coset = quotient_domain::<F>(n);
+    let size = coset.size();
+    // z(wX) on the coset is z(X) shifted by the ratio of both domains.
+    let shift = size / n;
+
+    let polys = [ax, bx, cx, z_x, pi_x];
+    let evals = cfg_iter!(polys)
+        .map(|poly| coset.fft(poly))
+        .collect::<Vec<_>>();
+    let [a, b, c, z, pi] = [0, 1, 2, 3, 4].map(|i| &evals[i]);
+    let CosetEvaluations {
+        q_l,
+        q_r,
+        q_m,
+        q_o,
+        q_c,
+        s_sigma_1,
+        s_sigma_2,
+        s_sigma_3,
+        l1,
+    } = &proving_key.coset_evals;
+
+    // The vanishing polynomial X^n - 1 only takes `shift` distinct values on the coset.
+    let mut vanishing_inverses = (0..shift)
+        .map(|i| coset.element(i).pow([n as u64]) - F::one())
+        .collect::<Vec<_>>();
+    batch_inversion(&mut vanishing_inverses);
+
+    let alpha_square = alpha.square();
+    let t_evals = cfg_into_iter!(0..size)
+        .map(|i| {
+            let x = coset.element(i);
+            let z_w = z[(i + shift) % size];
+
+            let line1 = a[i] * b[i] * q_m[i]
+                + a[i] * q_l[i]
+                + b[i] * q_r[i]
+                + c[i] * q_o[i]
+                + pi[i]
+                + q_c[i];
+            let line2 = (a[i] + *beta * x + gamma)
+                * (b[i] + *beta * k1 * x + gamma)
+                * (c[i] + *beta * k2 * x + gamma)
+                * z[i];
+            let line3 = (a[i] + *beta * s_sigma_1[i] + gamma)
+                * (b[i] + *beta * s_sigma_2[i] + gamma)
+                * (c[i] + *beta * s_sigma_3[i] + gamma)
+                * z_w;
+            let line4 = (z[i] - F::one()) * l1[i];
+
+            (line1 + *alpha * (line2 - line3) + alpha_square * line4)
+                * vanishing_inverses[i % shift]
+        })
+        .collect::<Vec<_>>();
+
+    DensePolynomial::from_coefficients_vec(coset.ifft(&t_evals))
 }
 
-/// Divides a polynomial by the vanishing polynomial of the given domain.
-/// Returns the quotient polynomial if the division is successful, otherwise returns an error indicating a remainder.
-pub(crate) fn l1_poly(domain: &GeneralEvaluationDomain<Fr>) -> Polynomial {
+/// Computes the first Lagrange polynomial L1(X) of the given domain.
+pub(crate) fn l1_poly<F: PrimeField>(domain: &GeneralEvaluationDomain<F>) -> Polynomial<F> {
     let n = domain.size();
-    let mut l1_e = vec![Fr::from(0); n];
-    l1_e[0] = Fr::from(1);
+    let mut l1_e = vec![F::zero(); n];
+    l1_e[0] = F::one();
     Evaluations::from_vec_and_domain(l1_e, *domain).interpolate()
 }
 
 /// Computes the linearization polynomial for the proof generation.
 /// This function computes various terms involving the provided parameters and polynomials.
 #[allow(clippy::too_many_arguments)]
-fn compute_linearisation_polynomial(
-    beta: &Fr,
-    gamma: &Fr,
-    alpha: &Fr,
-    eval_challenge: &Fr,
-    bar_a: &Fr,
-    bar_b: &Fr,
-    bar_c: &Fr,
-    bar_s_sigma_1: &Fr,
-    bar_s_sigma_2: &Fr,
-    bar_z_w: &Fr,
-    pi_e: &Fr,
-    tx_compact: &Polynomial,
-    z_x: &Polynomial,
-    ax: &Polynomial,
-    bx: &Polynomial,
-    cx: &Polynomial,
-    z_wx: &Polynomial,
-    domain: &GeneralEvaluationDomain<Fr>,
-    compiled_circuit: &CompiledCircuit,
-) -> Polynomial {
-    let mut line1 = compiled_circuit
-        .gate_constraints()
-        .q_mx()
-        .mul(*bar_a * *bar_b)
-        + compiled_circuit.gate_constraints().q_lx().mul(*bar_a)
-        + compiled_circuit.gate_constraints().q_rx().mul(*bar_b)
-        + compiled_circuit.gate_constraints().q_ox().mul(*bar_c)
-        + compiled_circuit.gate_constraints().q_cx().clone();
+fn compute_linearisation_polynomial<F: PrimeField, E: Pairing<ScalarField = F>>(
+    beta: &F,
+    gamma: &F,
+    alpha: &F,
+    eval_challenge: &F,
+    bar_a: &F,
+    bar_b: &F,
+    bar_c: &F,
+    bar_s_sigma_1: &F,
+    bar_s_sigma_2: &F,
+    bar_z_w: &F,
+    pi_e: &F,
+    tx_compact: &Polynomial<F>,
+    z_x: &Polynomial<F>,
+    domain: &GeneralEvaluationDomain<F>,
+    proving_key: &ProvingKey<E>,
+) -> Polynomial<F> {
+    let mut line1 = proving_key.q_mx.mul(*bar_a * *bar_b)
+        + proving_key.q_lx.mul(*bar_a)
+        + proving_key.q_rx.mul(*bar_b)
+        + proving_key.q_ox.mul(*bar_c)
+        + proving_key.q_cx.clone();
     line1.coeffs[0] += pi_e;
 
     let line2 = (*bar_a + *beta * eval_challenge + gamma)
-        * (*bar_b + *beta * compiled_circuit.copy_constraints().k1() * eval_challenge + gamma)
-        * (*bar_c + *beta * compiled_circuit.copy_constraints().k2() * eval_challenge + gamma)
+        * (*bar_b + *beta * proving_key.k1 * eval_challenge + gamma)
+        * (*bar_c + *beta * proving_key.k2 * eval_challenge + gamma)
         * alpha;
     let line2 = z_x.mul(line2);
 
@@ -509,55 +513,14 @@ fn compute_linearisation_polynomial(
         * (*bar_b + *beta * bar_s_sigma_2 + gamma)
         * bar_z_w
         * alpha;
-    let mut tmp2 = compiled_circuit.copy_constraints().s_sigma_3().mul(*beta);
+    let mut tmp2 = proving_key.s_sigma_3.mul(*beta);
     tmp2.coeffs[0] += *bar_c + gamma;
     let line3 = tmp2.mul(line3);
 
-    // check:
-    {
-        let line22 = (ax.clone() + DensePolynomial::from_coefficients_vec(vec![*gamma, *beta]))
-            .mul(
-                &(bx.clone()
-                    + DensePolynomial::from_coefficients_vec(vec![
-                        *gamma,
-                        *beta * compiled_circuit.copy_constraints().k1(),
-                    ])),
-            )
-            .mul(
-                &(cx.clone()
-                    + DensePolynomial::from_coefficients_vec(vec![
-                        *gamma,
-                        *beta * compiled_circuit.copy_constraints().k2(),
-                    ])),
-            )
-            .mul(*alpha)
-            .mul(z_x);
-
-        let line32 = (ax.clone()
-            + compiled_circuit.copy_constraints().s_sigma_1().mul(*beta)
-            + DensePolynomial::from_coefficients_vec(vec![*gamma]))
-        .mul(
-            &(bx.clone()
-                + compiled_circuit.copy_constraints().s_sigma_2().mul(*beta)
-                + DensePolynomial::from_coefficients_vec(vec![*gamma])),
-        )
-        .mul(
-            &(cx.clone()
-                + compiled_circuit.copy_constraints().s_sigma_3().mul(*beta)
-                + DensePolynomial::from_coefficients_vec(vec![*gamma])),
-        )
-        .mul(*alpha)
-        .mul(z_wx);
-
-        let diff2 = line32.evaluate(eval_challenge) - line22.evaluate(eval_challenge);
-        let cur = line3.evaluate(eval_challenge) - line2.evaluate(eval_challenge);
-        assert_eq!(diff2, cur, "Wrong: line2 or line3 of round 5");
-    }
-
     let line4 = {
         let l1_e = l1_poly(domain).evaluate(eval_challenge);
         let mut zx2 = z_x.clone();
-        zx2.coeffs[0] -= Fr::from(1);
+        zx2.coeffs[0] -= F::one();
         zx2.mul(l1_e).mul(alpha.square())
     };
 
@@ -570,14 +533,18 @@ fn compute_linearisation_polynomial(
 }
 
 /// Computes the commitments for round 1 of the proof generation process.
-fn commit_round1(
-    ax: &Polynomial,
-    bx: &Polynomial,
-    cx: &Polynomial,
-    scheme: &KzgScheme,
-) -> [KzgCommitment; 3] {
-    let c_ax = scheme.commit(ax);
-    let c_bx = scheme.commit(bx);
-    let c_cx = scheme.commit(cx);
-    [c_ax, c_bx, c_cx]
+///
+/// The wire polynomials are committed from their values in the Lagrange basis of the
+/// proving key, so only the blinding parts are committed in coefficient form.
+fn commit_round1<E: Pairing>(
+    wire_evals: &[Vec<E::ScalarField>; 3],
+    blinders: [&Polynomial<E::ScalarField>; 3],
+    lagrange_basis: &[E::G1Affine],
+    scheme: &KzgScheme<E>,
+) -> Result<[KzgCommitment<E>; 3], KzgError> {
+    let [c_ax, c_bx, c_cx] = wire_evals
+        .each_ref()
+        .map(|evals| KzgCommitment(msm(lagrange_basis, evals).into_affine()));
+    let [c_blind_a, c_blind_b, c_blind_c] = scheme.commit_all(blinders)?;
+    Ok([c_ax + c_blind_a, c_bx + c_blind_b, c_cx + c_blind_c])
 }
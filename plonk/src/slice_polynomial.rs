@@ -1,66 +1,85 @@
 use std::ops::Mul;
 
-use ark_bls12_381::Fr;
-use ark_ff::{One, Zero};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
 use ark_poly::univariate::SparsePolynomial;
 use ark_poly::{DenseUVPolynomial, Polynomial as Poly};
 
 use kzg::commitment::KzgCommitment;
+use kzg::error::KzgError;
 use kzg::scheme::KzgScheme;
+use kzg::srs::max_degree;
 
 use crate::types::Polynomial;
 
 /// Struct representing a slice polynomial.
 #[derive(Debug)]
-pub(crate) struct SlicePoly {
-    slices: [Polynomial; 3],
+pub(crate) struct SlicePoly<F: PrimeField> {
+    slices: [Polynomial<F>; 3],
     degree: usize,
 }
 
-impl SlicePoly {
-    /// Creates a new slice polynomial with the given polynomial and degree.
-    pub fn new(polynomial: Polynomial) -> Self {
-        let coefficients = polynomial.coeffs;
-
-        let mut tmp = coefficients.len() / 3;
-        if tmp * 3 < coefficients.len() {
-            tmp += 1;
+impl<F: PrimeField> SlicePoly<F> {
+    /// Splits the quotient polynomial of a circuit of size `n` in three blinded slices.
+    ///
+    /// The first two slices have `slice_size(n)` coefficients, and the last one the rest,
+    /// so the split only depends on the size of the circuit. The blinding scalars
+    /// `[b10, b11]` are then moved between the slices:
+    ///
+    /// t_lo(X) + b10 X^{n+2}, t_mid(X) - b10 + b11 X^{n+2}, t_hi(X) - b11
+    ///
+    /// which hides the slices without changing the compacted polynomial. Fails if the
+    /// polynomial has more than 3n + 6 coefficients, which happens when the witness does
+    /// not satisfy the circuit.
+    pub fn new(polynomial: Polynomial<F>, n: usize, blinders: [F; 2]) -> Result<Self, String> {
+        let size = slice_size(n);
+        if polynomial.coeffs.len() > 3 * size {
+            return Err(format!(
+                "Prove: quotient degree {} exceeds 3n + 5 = {}",
+                polynomial.degree(),
+                3 * size - 1
+            ));
         }
-
-        let mut slices = [(); 3].map(|_| Polynomial::zero());
-        coefficients
-            .chunks(tmp)
-            .map(Polynomial::from_coefficients_slice)
-            .enumerate()
-            .for_each(|(index, slice)| {
-                slices[index] = slice;
-            });
-
-        Self {
-            slices,
-            degree: (tmp - 1),
+        let mut coefficients = polynomial.coeffs;
+        let mut hi = coefficients.split_off((2 * size).min(coefficients.len()));
+        let mut mid = coefficients.split_off(size.min(coefficients.len()));
+        let mut lo = coefficients;
+
+        let [b10, b11] = blinders;
+        for (slice, lower, upper) in [
+            (&mut lo, F::zero(), b10),
+            (&mut mid, -b10, b11),
+            (&mut hi, -b11, F::zero()),
+        ] {
+            slice.resize(size + 1, F::zero());
+            slice[0] += lower;
+            slice[size] += upper;
         }
-    }
 
-    /// Gets the degree of the slice polynomial.
-    pub fn get_degree(&self) -> usize {
-        self.degree
+        Ok(Self {
+            slices: [lo, mid, hi].map(Polynomial::from_coefficients_vec),
+            degree: size - 1,
+        })
     }
 
     /// Commits to each slice polynomial using the provided KZG scheme.
-    pub fn commit(&self, scheme: &KzgScheme) -> [KzgCommitment; 3] {
-        self.slices.clone().map(|slice| scheme.commit(&slice))
+    pub fn commit<E: Pairing<ScalarField = F>>(
+        &self,
+        scheme: &KzgScheme<E>,
+    ) -> Result<[KzgCommitment<E>; 3], KzgError> {
+        let [lo, mid, hi] = &self.slices;
+        scheme.commit_all([lo, mid, hi])
     }
 
     /// Compacts the slice polynomial at the given point.
-    pub fn compact(&self, point: &Fr) -> Polynomial {
+    pub fn compact(&self, point: &F) -> Polynomial<F> {
         self.slices
             .iter()
             .enumerate()
             .map(|(index, slice)| {
                 let exponent = SparsePolynomial::from_coefficients_slice(&[(
                     (self.degree + 1) * index,
-                    Fr::one(),
+                    F::one(),
                 )]);
                 slice.mul(exponent.evaluate(point))
             })
@@ -69,9 +88,20 @@ impl SlicePoly {
     }
 }
 
+/// Returns the number of coefficients of the first two slices of the quotient polynomial of
+/// a circuit of size `n`.
+///
+/// The quotient polynomial has degree at most 3n + 5, so the last slice has at most as
+/// many coefficients, and the blinded slices have degree at most n + 2, the maximum degree
+/// an SRS for the circuit supports.
+pub(crate) fn slice_size(n: usize) -> usize {
+    max_degree(n)
+}
+
 #[cfg(test)]
 mod tests {
-    use ark_ff::{Field, UniformRand};
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, UniformRand, Zero};
     use ark_std::test_rng;
 
     use super::*;
@@ -83,8 +113,8 @@ mod tests {
         let coeffs: Vec<Fr> = (0..12).map(|_| Fr::rand(rng)).collect();
         let poly = Polynomial::from_coefficients_vec(coeffs.clone());
 
-        let slice_poly = SlicePoly::new(poly.clone());
-        assert_eq!(slice_poly.get_degree(), degree);
+        let slice_poly = SlicePoly::new(poly.clone(), 2, [Fr::zero(); 2]).unwrap();
+        assert_eq!(slice_poly.degree, degree);
 
         // Test the slices
         for (i, slice) in slice_poly.slices.iter().enumerate() {
@@ -109,4 +139,37 @@ mod tests {
 
         assert_eq!(compacted_poly, expected_poly);
     }
+
+    #[test]
+    fn test_blinded_slice_poly() {
+        let rng = &mut test_rng();
+        let poly = Polynomial::<Fr>::rand(11, rng);
+        let blinders = [Fr::rand(rng), Fr::rand(rng)];
+
+        let plain = SlicePoly::new(poly.clone(), 2, [Fr::zero(); 2]).unwrap();
+        let blinded = SlicePoly::new(poly, 2, blinders).unwrap();
+        for (plain, blinded) in plain.slices.iter().zip(&blinded.slices) {
+            assert_ne!(plain, blinded);
+            assert!(blinded.degree() <= slice_size(2));
+        }
+
+        // The blinders cancel out at the point of compaction.
+        let point = Fr::rand(rng);
+        assert_eq!(
+            plain.compact(&point).evaluate(&point),
+            blinded.compact(&point).evaluate(&point)
+        );
+    }
+
+    #[test]
+    fn test_slice_poly_degree_exceeds() {
+        let rng = &mut test_rng();
+        let blinders = [Fr::rand(rng), Fr::rand(rng)];
+        // A circuit of size 2 has a quotient of degree at most 11.
+        assert!(SlicePoly::new(Polynomial::<Fr>::rand(11, rng), 2, blinders).is_ok());
+        assert_eq!(
+            SlicePoly::new(Polynomial::<Fr>::rand(12, rng), 2, blinders).unwrap_err(),
+            "Prove: quotient degree 12 exceeds 3n + 5 = 11"
+        );
+    }
 }
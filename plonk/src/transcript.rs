@@ -0,0 +1,141 @@
+use std::marker::PhantomData;
+
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use digest::Digest;
+
+/// Fiat-Shamir transcript of a proof.
+///
+/// Every message is absorbed with a label, and the state is the hash of the previous state
+/// and the labelled message, so the prover and the verifier derive the same challenges
+/// only if they absorb the same messages in the same order.
+///
+/// Challenges are derived with hash-to-field: the state is expanded to 128 bits more than
+/// the size of the field and reduced, so they are uniform over the whole field. Each
+/// challenge is absorbed back into the transcript.
+#[derive(Clone)]
+pub struct Transcript<T: Digest> {
+    state: Vec<u8>,
+
+    // Phantom data for annotation purposes.
+    _phantom_data: PhantomData<T>,
+}
+
+impl<T: Digest> Transcript<T> {
+    /// Creates a transcript for the protocol of the given label.
+    pub fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            state: Vec::new(),
+            _phantom_data: PhantomData,
+        };
+        transcript.append_message(b"dom-sep", label);
+        transcript
+    }
+
+    /// Absorbs a labelled message.
+    pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        let mut hasher = T::new();
+        hasher.update(&self.state);
+        hasher.update((label.len() as u64).to_le_bytes());
+        hasher.update(label);
+        hasher.update((message.len() as u64).to_le_bytes());
+        hasher.update(message);
+        self.state = hasher.finalize().to_vec();
+    }
+
+    /// Absorbs a labelled point, in its uncompressed encoding.
+    pub fn append_point<P: AffineRepr>(&mut self, label: &'static [u8], point: &P) {
+        self.append_serializable(label, point);
+    }
+
+    /// Absorbs a labelled scalar, in its little-endian encoding.
+    pub fn append_scalar<F: PrimeField>(&mut self, label: &'static [u8], scalar: &F) {
+        self.append_serializable(label, scalar);
+    }
+
+    /// Derives a labelled challenge.
+    pub fn challenge_scalar<F: PrimeField>(&mut self, label: &'static [u8]) -> F {
+        // 128 bits more than the field, so the reduction has a negligible bias.
+        let length = (F::MODULUS_BIT_SIZE as usize + 128).div_ceil(8);
+        let mut bytes = Vec::with_capacity(length);
+        let mut counter = 0u8;
+        while bytes.len() < length {
+            let mut hasher = T::new();
+            hasher.update(&self.state);
+            hasher.update((label.len() as u64).to_le_bytes());
+            hasher.update(label);
+            hasher.update([counter]);
+            bytes.extend_from_slice(&hasher.finalize());
+            counter += 1;
+        }
+        let challenge = F::from_le_bytes_mod_order(&bytes[..length]);
+        self.append_scalar(label, &challenge);
+        challenge
+    }
+
+    /// Derives several challenges with the same label.
+    pub fn challenge_scalars<F: PrimeField, const N: usize>(
+        &mut self,
+        label: &'static [u8],
+    ) -> [F; N] {
+        [(); N].map(|_| self.challenge_scalar(label))
+    }
+
+    fn append_serializable<S: CanonicalSerialize>(&mut self, label: &'static [u8], value: &S) {
+        let mut message = Vec::with_capacity(value.uncompressed_size());
+        value
+            .serialize_uncompressed(&mut message)
+            .expect("Serializing to a vector should be infallible!");
+        self.append_message(label, &message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fr, G1Affine};
+    use ark_ff::BigInteger;
+    use sha2::Sha256;
+
+    use super::*;
+
+    #[test]
+    fn transcript_is_deterministic() {
+        let transcript = || {
+            let mut transcript = Transcript::<Sha256>::new(b"test");
+            transcript.append_point(b"point", &G1Affine::generator());
+            transcript.append_scalar(b"scalar", &Fr::from(3));
+            transcript
+        };
+        let [a, b] = transcript().challenge_scalars::<Fr, 2>(b"challenge");
+        let [c, d] = transcript().challenge_scalars::<Fr, 2>(b"challenge");
+        assert_eq!((a, b), (c, d));
+        assert_ne!(a, b, "each challenge should be absorbed");
+    }
+
+    #[test]
+    fn labels_separate_messages() {
+        let challenge = |protocol, label: &'static [u8], message: &[u8]| {
+            let mut transcript = Transcript::<Sha256>::new(protocol);
+            transcript.append_message(label, message);
+            transcript.challenge_scalar::<Fr>(b"challenge")
+        };
+        let challenge_ab = challenge(b"test", b"a", b"b");
+        assert_ne!(challenge_ab, challenge(b"other", b"a", b"b"));
+        assert_ne!(challenge_ab, challenge(b"test", b"b", b"b"));
+        // The lengths are absorbed, so moving a byte from the label to the message matters.
+        assert_ne!(challenge_ab, challenge(b"test", b"", b"ab"));
+    }
+
+    #[test]
+    fn challenges_are_full_width() {
+        let mut transcript = Transcript::<Sha256>::new(b"test");
+        let challenges = transcript.challenge_scalars::<Fr, 8>(b"challenge");
+        // A challenge of at most 64 bits has its top bytes cleared with certainty.
+        assert!(challenges
+            .iter()
+            .any(|challenge| challenge.into_bigint().to_bytes_le()[8..]
+                .iter()
+                .any(|byte| *byte != 0)));
+    }
+}
@@ -1,54 +1,103 @@
-use std::ops::Mul;
+use std::iter::{successors, zip};
 
-use ark_bls12_381::{Bls12_381, Fr};
 use ark_ec::pairing::Pairing;
-use ark_ff::{BigInt, Field};
-use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInt, Field, One, Zero};
+use ark_serialize::Valid;
 use digest::Digest;
 
 use kzg::commitment::KzgCommitment;
+use kzg::opening::KzgMultiOpening;
 use kzg::scheme::KzgScheme;
 use kzg::srs::Srs;
 
-use crate::challenge::ChallengeGenerator;
 use crate::compiled_circuit::CompiledCircuit;
-use crate::prover::Proof;
+use crate::key::VerifyingKey;
+use crate::prover::{shplonk_points, OpeningScheme, Proof, TRANSCRIPT_LABEL};
+use crate::slice_polynomial::slice_size;
+use crate::transcript::Transcript;
 
 /// Verifies a zero-knowledge proof for a compiled circuit.
 ///
-pub fn verify<T: Digest + Default>(
-    compiled_circuit: &CompiledCircuit,
-    srs: Srs,
-    proof: Proof,
+/// The public inputs must be the ones the proof was generated with.
+pub fn verify<T: Digest + Default, E: Pairing>(
+    compiled_circuit: &CompiledCircuit<E::ScalarField>,
+    srs: Srs<E>,
+    proof: Proof<E>,
+    public_inputs: &[E::ScalarField],
 ) -> Result<(), String> {
-    println!("Verifying...");
+    verify_with_opening::<T, E>(
+        compiled_circuit,
+        srs,
+        proof,
+        public_inputs,
+        OpeningScheme::Kzg,
+    )
+}
+
+/// Verifies a zero-knowledge proof for a compiled circuit using the given opening scheme.
+///
+/// The opening scheme must be the one the proof was generated with.
+pub fn verify_with_opening<T: Digest + Default, E: Pairing>(
+    compiled_circuit: &CompiledCircuit<E::ScalarField>,
+    srs: Srs<E>,
+    proof: Proof<E>,
+    public_inputs: &[E::ScalarField],
+    opening: OpeningScheme,
+) -> Result<(), String> {
+    srs.validate()?;
 
     #[cfg(test)]
     println!("Precompute");
 
-    let scheme = KzgScheme::new(srs);
+    let verifying_key = VerifyingKey::new(compiled_circuit, &srs)?;
+    verify_with_key::<T, E>(&verifying_key, proof, public_inputs, opening)
+}
 
-    let (q_m_c, q_l_c, q_r_c, q_o_c, q_c_c, s_sigma_1_c, s_sigma_2_c, s_sigma_3_c) =
-        get_circuit_commitment(compiled_circuit, &scheme);
+/// Verifies a zero-knowledge proof with a verifying key from `setup`.
+///
+/// The opening scheme must be the one the proof was generated with. The proof is rejected
+/// if any of its points is off the curve or outside G1. The SRS of the verifying key is not
+/// checked again, so the key must come from `setup` or a validated deserialization.
+pub fn verify_with_key<T: Digest + Default, E: Pairing>(
+    verifying_key: &VerifyingKey<E>,
+    proof: Proof<E>,
+    public_inputs: &[E::ScalarField],
+    opening: OpeningScheme,
+) -> Result<(), String> {
+    println!("Verifying...");
+
+    // A proof not deserialized with validation may have points off the curve or outside G1.
+    proof
+        .check()
+        .map_err(|e| format!("Verify: Invalid proof: {e}"))?;
+    let scheme = KzgScheme::new(verifying_key.srs.clone());
+
+    let [q_m_c, q_l_c, q_r_c, q_o_c, q_c_c, s_sigma_1_c, s_sigma_2_c, s_sigma_3_c] = [
+        &verifying_key.com_q_mx,
+        &verifying_key.com_q_lx,
+        &verifying_key.com_q_rx,
+        &verifying_key.com_q_ox,
+        &verifying_key.com_q_cx,
+        &verifying_key.com_s_sigma_1,
+        &verifying_key.com_s_sigma_2,
+        &verifying_key.com_s_sigma_3,
+    ]
+    .map(Clone::clone);
 
     #[cfg(test)]
     println!("Verify challenges");
-    let (alpha, beta, gamma, evaluation_challenge, v, u) = verify_challenges::<T>(&proof, &scheme);
-
-    if u != proof.u {
-        return Err(String::from("Verify: Challenge verification failed."));
-    }
+    let [alpha, beta, gamma, evaluation_challenge, v, u] =
+        verify_challenges::<T, E>(verifying_key, &proof, public_inputs, opening);
 
-    let domain = <GeneralEvaluationDomain<Fr>>::new(compiled_circuit.size).unwrap();
-    let w = domain.element(1);
+    let w = verifying_key.omega;
 
-    let z_h_e = evaluation_challenge.pow(BigInt::new([domain.size() as u64])) - Fr::from(1);
-    let l_1_e =
-        z_h_e / (Fr::from(compiled_circuit.size as u128) * (evaluation_challenge - Fr::from(1)));
-    let p_i_e = compiled_circuit
-        .gate_constraints()
-        .pi_x()
-        .evaluate(&evaluation_challenge);
+    let z_h_e =
+        evaluation_challenge.pow(BigInt::new([verifying_key.n as u64])) - E::ScalarField::one();
+    let l_1_e = z_h_e
+        / (E::ScalarField::from(verifying_key.n as u128)
+            * (evaluation_challenge - E::ScalarField::one()));
+    let p_i_e = verifying_key.public_input_eval(public_inputs, evaluation_challenge)?;
 
     #[cfg(test)]
     println!("Compute r0");
@@ -61,93 +110,140 @@ pub fn verify<T: Digest + Default>(
             * proof.bar_z_w;
 
     #[cfg(test)]
-    println!("Compute [D]");
-
-    let d_line1 = q_m_c.mul(proof.bar_a * proof.bar_b)
-        + q_l_c.mul(proof.bar_a)
-        + q_r_c.mul(proof.bar_b)
-        + q_o_c.mul(proof.bar_c)
-        + q_c_c;
-
-    let d_line2 = proof.z_commit.mul(
-        (proof.bar_a + beta * evaluation_challenge + gamma)
-            * (proof.bar_b
-                + beta * compiled_circuit.copy_constraints().k1() * evaluation_challenge
-                + gamma)
-            * (proof.bar_c
-                + beta * compiled_circuit.copy_constraints().k2() * evaluation_challenge
-                + gamma)
-            * alpha
-            + l_1_e * alpha * alpha
-            + u,
-    );
-
-    let d_line3 = s_sigma_3_c.mul(
-        (proof.bar_a + beta * proof.bar_s_sigma_1 + gamma)
-            * (proof.bar_b + beta * proof.bar_s_sigma_2 + gamma)
-            * alpha
-            * beta
-            * proof.bar_z_w,
-    );
-
-    let d_line4 = (proof.t_lo_commit
-        + proof
-            .t_mid_commit
-            .mul(evaluation_challenge.pow(BigInt::new([proof.degree as u64 + 1])))
-        + proof
-            .t_hi_commit
-            .mul(evaluation_challenge.pow(BigInt::new([proof.degree as u64 * 2 + 2]))))
-    .mul(z_h_e);
-
-    let d = d_line1 + d_line2 - d_line3 - d_line4;
-
-    #[cfg(test)]
-    println!("Compute [F]");
-
-    let f = d
-        + proof.a_commit.mul(v)
-        + proof.b_commit.mul(v * v)
-        + proof.c_commit.mul(v * v * v)
-        + s_sigma_1_c.mul(v * v * v * v)
-        + s_sigma_2_c.mul(v * v * v * v * v);
-
-    #[cfg(test)]
-    println!("Compute [E]");
-    let e = -r_0
-        + v * proof.bar_a
-        + v * v * proof.bar_b
-        + v * v * v * proof.bar_c
-        + v * v * v * v * proof.bar_s_sigma_1
-        + v * v * v * v * v * proof.bar_s_sigma_2
-        + u * proof.bar_z_w;
-    let e = scheme.commit_para(e);
-
-    #[cfg(test)]
-    println!("Compute left side of paring");
-
-    let pairing_left_side = Bls12_381::pairing(
-        (proof.w_ev_x_commit.clone() + proof.w_ev_wx_commit.clone().mul(u)).0,
-        scheme.g2s(),
-    );
-
-    #[cfg(test)]
-    println!("Compute right side of paring");
-    let pairing_right_side = Bls12_381::pairing(
-        (proof.w_ev_x_commit.clone().mul(evaluation_challenge)
-            + proof
-                .w_ev_wx_commit
-                .clone()
-                .mul(u * evaluation_challenge * w)
-            + f
-            - e)
-            .0,
-        scheme.g2(),
-    );
+    println!("Compute [r]");
+
+    // [r] = [D] + r_0 [1] is the commitment to the linearisation polynomial, which vanishes
+    // at the evaluation challenge. Its terms are the bases and scalars of a single
+    // multi-scalar multiplication, which the KZG opening extends with multiples of [z] and
+    // [1] folded in their scalars.
+    let zeta_slice = evaluation_challenge.pow([slice_size(verifying_key.n) as u64]);
+    let linearisation_terms = |z_shift: E::ScalarField, constant_shift: E::ScalarField| {
+        let bases = vec![
+            q_m_c.0,
+            q_l_c.0,
+            q_r_c.0,
+            q_o_c.0,
+            q_c_c.0,
+            proof.z_commit.0,
+            s_sigma_3_c.0,
+            proof.t_lo_commit.0,
+            proof.t_mid_commit.0,
+            proof.t_hi_commit.0,
+            verifying_key.srs.g1_points()[0],
+        ];
+        let scalars = vec![
+            proof.bar_a * proof.bar_b,
+            proof.bar_a,
+            proof.bar_b,
+            proof.bar_c,
+            E::ScalarField::one(),
+            (proof.bar_a + beta * evaluation_challenge + gamma)
+                * (proof.bar_b + beta * verifying_key.k1 * evaluation_challenge + gamma)
+                * (proof.bar_c + beta * verifying_key.k2 * evaluation_challenge + gamma)
+                * alpha
+                + l_1_e * alpha * alpha
+                + z_shift,
+            -(proof.bar_a + beta * proof.bar_s_sigma_1 + gamma)
+                * (proof.bar_b + beta * proof.bar_s_sigma_2 + gamma)
+                * alpha
+                * beta
+                * proof.bar_z_w,
+            // The split of the quotient polynomial only depends on the size of the circuit.
+            -z_h_e,
+            -z_h_e * zeta_slice,
+            -z_h_e * zeta_slice.square(),
+            r_0 + constant_shift,
+        ];
+        (bases, scalars)
+    };
+
+    let accepted = match opening {
+        OpeningScheme::Kzg => {
+            #[cfg(test)]
+            println!("Compute [F] - [E]");
+
+            // [F] - [E] adds the wires and the permutations batched with v, the evaluation of
+            // z at ωζ batched with u, and takes their evaluations off the constant term.
+            let powers_of_v = successors(Some(v), |power| Some(*power * v))
+                .take(5)
+                .collect::<Vec<_>>();
+            let evaluations = [
+                proof.bar_a,
+                proof.bar_b,
+                proof.bar_c,
+                proof.bar_s_sigma_1,
+                proof.bar_s_sigma_2,
+            ];
+            let e = zip(&powers_of_v, evaluations)
+                .map(|(power, evaluation)| *power * evaluation)
+                .sum::<E::ScalarField>()
+                + u * proof.bar_z_w;
+            let (mut bases, mut scalars) = linearisation_terms(u, -e);
+            bases.extend([
+                proof.a_commit.0,
+                proof.b_commit.0,
+                proof.c_commit.0,
+                s_sigma_1_c.0,
+                s_sigma_2_c.0,
+            ]);
+            scalars.extend(powers_of_v);
+
+            // The openings are moved to the right side, so both sides are a single
+            // multi-scalar multiplication, and the check a single multi-pairing:
+            // e([W_ζ] + u [W_ζω], [x]) = e(ζ [W_ζ] + uζω [W_ζω] + [F] - [E], [1])
+            bases.extend([proof.w_ev_x_commit.0, proof.w_ev_wx_commit.0]);
+            scalars.extend([evaluation_challenge, u * evaluation_challenge * w]);
+
+            #[cfg(test)]
+            println!("Compute the sides of the pairing");
+            let left_side = proof.w_ev_x_commit.0 + proof.w_ev_wx_commit.0 * u;
+            let right_side = E::G1::msm_unchecked(&bases, &scalars);
+
+            E::multi_pairing([left_side, -right_side], [scheme.g2s(), scheme.g2()]).is_zero()
+        }
+        OpeningScheme::Shplonk => {
+            let zero = E::ScalarField::zero();
+            let (bases, scalars) = linearisation_terms(zero, zero);
+            let r_commit = KzgCommitment(E::G1::msm_unchecked(&bases, &scalars).into_affine());
+
+            let commitments = [
+                r_commit,
+                proof.a_commit,
+                proof.b_commit,
+                proof.c_commit,
+                s_sigma_1_c,
+                s_sigma_2_c,
+                proof.z_commit,
+            ];
+            let evaluations = [
+                E::ScalarField::zero(),
+                proof.bar_a,
+                proof.bar_b,
+                proof.bar_c,
+                proof.bar_s_sigma_1,
+                proof.bar_s_sigma_2,
+                proof.bar_z_w,
+            ]
+            .map(|evaluation| vec![evaluation]);
+            let multi_opening = KzgMultiOpening(proof.w_ev_x_commit.0, proof.w_ev_wx_commit.0);
+
+            scheme
+                .multi_verify(
+                    &commitments,
+                    &shplonk_points(evaluation_challenge, w),
+                    &evaluations,
+                    v,
+                    u,
+                    &multi_opening,
+                )
+                .is_ok()
+        }
+    };
 
     #[cfg(test)]
     println!("Check pairing");
 
-    if pairing_left_side != pairing_right_side {
+    if !accepted {
         return Err(String::from("Verify: Pairing failed, rejected"));
     }
 
@@ -156,75 +252,72 @@ pub fn verify<T: Digest + Default>(
     Ok(())
 }
 
-/// Gets commitments of the circuit via compiled_circuit
-fn get_circuit_commitment(
-    compiled_circuit: &CompiledCircuit,
-    scheme: &KzgScheme,
-) -> (
-    KzgCommitment,
-    KzgCommitment,
-    KzgCommitment,
-    KzgCommitment,
-    KzgCommitment,
-    KzgCommitment,
-    KzgCommitment,
-    KzgCommitment,
-) {
-    let q_m_c = scheme.commit(compiled_circuit.gate_constraints().q_mx());
-    let q_l_c = scheme.commit(compiled_circuit.gate_constraints().q_lx());
-    let q_r_c = scheme.commit(compiled_circuit.gate_constraints().q_rx());
-    let q_o_c = scheme.commit(compiled_circuit.gate_constraints().q_ox());
-    let q_c_c = scheme.commit(compiled_circuit.gate_constraints().q_cx());
-    let s_sigma1_c = scheme.commit(compiled_circuit.copy_constraints().s_sigma_1());
-    let s_sigma2_c = scheme.commit(compiled_circuit.copy_constraints().s_sigma_2());
-    let s_sigma3_c = scheme.commit(compiled_circuit.copy_constraints().s_sigma_3());
-
-    (
-        q_m_c, q_l_c, q_r_c, q_o_c, q_c_c, s_sigma1_c, s_sigma2_c, s_sigma3_c,
-    )
-}
-
 /// Verifies Fiat-Shamir challenges.
-fn verify_challenges<T: Digest + Default>(
-    proof: &Proof,
-    scheme: &KzgScheme,
-) -> (Fr, Fr, Fr, Fr, Fr, Fr) {
-    let commitments = [
-        proof.a_commit.clone(),
-        proof.b_commit.clone(),
-        proof.c_commit.clone(),
-    ];
-    let mut challenge = ChallengeGenerator::<T>::from_commitments(&commitments);
-    let [beta, gamma] = challenge.generate_challenges();
-    challenge.feed(&proof.z_commit);
-    let [alpha] = challenge.generate_challenges();
-    challenge.feed(&proof.t_lo_commit);
-    challenge.feed(&proof.t_mid_commit);
-    challenge.feed(&proof.t_hi_commit);
-    let [evaluation_challenge] = challenge.generate_challenges();
-
-    challenge.feed(&scheme.commit_para(proof.bar_a));
-    challenge.feed(&scheme.commit_para(proof.bar_b));
-    challenge.feed(&scheme.commit_para(proof.bar_c));
-    challenge.feed(&scheme.commit_para(proof.bar_s_sigma_1));
-    challenge.feed(&scheme.commit_para(proof.bar_s_sigma_2));
-    challenge.feed(&scheme.commit_para(proof.bar_z_w));
-    let [v] = challenge.generate_challenges();
-
-    challenge.feed(&proof.w_ev_x_commit);
-    challenge.feed(&proof.w_ev_wx_commit);
-
-    let [u] = challenge.generate_challenges();
-
-    (alpha, beta, gamma, evaluation_challenge, v, u)
+///
+/// The transcript is the one of the prover: the verifying key, the public inputs and the
+/// messages of each round, in order.
+fn verify_challenges<T: Digest, E: Pairing>(
+    verifying_key: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: &[E::ScalarField],
+    opening: OpeningScheme,
+) -> [E::ScalarField; 6] {
+    let mut transcript = Transcript::<T>::new(TRANSCRIPT_LABEL);
+    verifying_key.append_to_transcript(&mut transcript);
+    for public_input in public_inputs {
+        transcript.append_scalar(b"pi", public_input);
+    }
+
+    transcript.append_point(b"a", proof.a_commit.inner());
+    transcript.append_point(b"b", proof.b_commit.inner());
+    transcript.append_point(b"c", proof.c_commit.inner());
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
+
+    transcript.append_point(b"z", proof.z_commit.inner());
+    let alpha = transcript.challenge_scalar(b"alpha");
+
+    transcript.append_point(b"t_lo", proof.t_lo_commit.inner());
+    transcript.append_point(b"t_mid", proof.t_mid_commit.inner());
+    transcript.append_point(b"t_hi", proof.t_hi_commit.inner());
+    let evaluation_challenge = transcript.challenge_scalar(b"zeta");
+
+    transcript.append_scalar(b"bar_a", &proof.bar_a);
+    transcript.append_scalar(b"bar_b", &proof.bar_b);
+    transcript.append_scalar(b"bar_c", &proof.bar_c);
+    transcript.append_scalar(b"bar_s_sigma_1", &proof.bar_s_sigma_1);
+    transcript.append_scalar(b"bar_s_sigma_2", &proof.bar_s_sigma_2);
+    transcript.append_scalar(b"bar_z_w", &proof.bar_z_w);
+    let v = transcript.challenge_scalar(b"v");
+
+    transcript.append_point(b"w_ev_x", proof.w_ev_x_commit.inner());
+    if opening == OpeningScheme::Kzg {
+        transcript.append_point(b"w_ev_wx", proof.w_ev_wx_commit.inner());
+    }
+    let u = transcript.challenge_scalar(b"u");
+
+    [alpha, beta, gamma, evaluation_challenge, v, u]
 }
 
 #[cfg(test)]
 mod tests {
+    use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine};
+    use ark_ec::AffineRepr;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    use kzg::commitment::KzgCommitment;
     use sha2::Sha256;
 
+    use kzg::mmap::MappedSrs;
+
     use crate::circuit::Circuit;
-    use crate::prover::generate_proof;
+    use crate::hash::{CkbBlake2b, Poseidon, TranscriptHash};
+    use crate::key::setup;
+    use crate::parser::Parser;
+    use crate::prover::{
+        generate_proof, generate_proof_with_key, generate_proof_with_opening,
+        generate_proof_with_scheme,
+    };
 
     use super::*;
 
@@ -259,14 +352,15 @@ mod tests {
 
         let compiled_circuit = circuit.compile().unwrap();
 
-        let srs = Srs::new(compiled_circuit.size);
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
 
-        let proof = generate_proof::<Sha256>(&compiled_circuit, srs.clone());
-        assert!(verify::<Sha256>(&compiled_circuit, srs, proof).is_ok());
+        let proof =
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs.clone())
+                .unwrap();
+        assert!(verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_ok());
     }
 
     #[test]
-    #[should_panic]
     fn verifier_rejected_test_01() {
         // check: x^2 + y^2 = z^2
         let mut circuit = Circuit::default();
@@ -296,10 +390,12 @@ mod tests {
         );
         let compiled_circuit = circuit.compile().unwrap();
 
-        let srs = Srs::new(compiled_circuit.size);
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
 
-        let proof = generate_proof::<Sha256>(&compiled_circuit, srs.clone());
-        assert!(verify::<Sha256>(&compiled_circuit, srs, proof).is_ok());
+        // 9 + 16 != 20, so the prover rejects the witness.
+        assert!(
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs).is_err()
+        );
     }
 
     #[test]
@@ -347,14 +443,17 @@ mod tests {
             (0, 6, Fr::from(3)),
             (1, 6, Fr::from(0)),
             (1, 2, Fr::from(3)),
+            Fr::from(3),
             Fr::from(0),
         );
         let compiled_circuit = circuit.compile().unwrap();
 
-        let srs = Srs::new(compiled_circuit.size);
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
 
-        let proof = generate_proof::<Sha256>(&compiled_circuit, srs.clone());
-        assert!(verify::<Sha256>(&compiled_circuit, srs, proof).is_ok());
+        let proof =
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs.clone())
+                .unwrap();
+        assert!(verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_ok());
     }
 
     #[test]
@@ -375,9 +474,492 @@ mod tests {
         );
         let compiled_circuit = circuit.compile().unwrap();
 
-        let srs = Srs::new(compiled_circuit.size);
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
 
-        let proof = generate_proof::<Sha256>(&compiled_circuit, srs.clone());
-        assert!(verify::<Sha256>(&compiled_circuit, srs, proof).is_ok());
+        let proof =
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs.clone())
+                .unwrap();
+        assert!(verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_ok());
+    }
+
+    #[test]
+    fn verifier_shplonk_test() {
+        // check xyz = 6
+        let mut circuit = Circuit::default();
+        circuit.add_multiplication_gate(
+            (0, 0, Fr::from(1)),
+            (1, 0, Fr::from(2)),
+            (0, 1, Fr::from(2)),
+            Fr::from(0),
+        );
+        circuit.add_multiplication_gate(
+            (2, 0, Fr::from(2)),
+            (1, 1, Fr::from(3)),
+            (2, 1, Fr::from(6)),
+            Fr::from(0),
+        );
+        let compiled_circuit = circuit.compile().unwrap();
+
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+
+        let proof = generate_proof_with_opening::<Sha256, _>(
+            &compiled_circuit,
+            circuit.witness(),
+            &[],
+            srs.clone(),
+            OpeningScheme::Shplonk,
+        )
+        .unwrap();
+        assert!(verify_with_opening::<Sha256, _>(
+            &compiled_circuit,
+            srs.clone(),
+            proof,
+            &[],
+            OpeningScheme::Shplonk
+        )
+        .is_ok());
+
+        // A SHPLONK proof is rejected by the KZG verifier.
+        let proof = generate_proof_with_opening::<Sha256, _>(
+            &compiled_circuit,
+            circuit.witness(),
+            &[],
+            srs.clone(),
+            OpeningScheme::Shplonk,
+        )
+        .unwrap();
+        assert!(verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_err());
+    }
+
+    #[test]
+    fn verifier_long_chain_test() {
+        // check 0 + 1 + ... + 1 = 30, each gate adding one to the output of the previous one
+        let gates = 30;
+        let mut circuit = Circuit::default();
+        for i in 0..gates {
+            let left = if i == 0 { (0, 0) } else { (2, i - 1) };
+            let output = if i == gates - 1 { (2, i) } else { (0, i + 1) };
+            circuit.add_addition_gate(
+                (left.0, left.1, Fr::from(i as u64)),
+                (1, i, Fr::from(1)),
+                (output.0, output.1, Fr::from(i as u64 + 1)),
+                Fr::from(0),
+            );
+        }
+        let compiled_circuit = circuit.compile().unwrap();
+
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+
+        let proof =
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs.clone())
+                .unwrap();
+        assert!(verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_ok());
+    }
+
+    #[test]
+    fn verifier_many_witnesses_test() {
+        // One compiled circuit of x*y + 3*x^2 + x*y*z = 11 is proven with several witnesses.
+        let parse = |x: u64, y: u64, z: u64| {
+            let mut parser = Parser::default();
+            parser.add_witness("x", Fr::from(x));
+            parser.add_witness("y", Fr::from(y));
+            parser.add_witness("z", Fr::from(z));
+            parser.parse("x*y+3*x^2+x*y*z=11")
+        };
+        let circuit = parse(1, 2, 3);
+        let compiled_circuit = circuit.compile().unwrap();
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+        let (proving_key, verifying_key) = setup(&compiled_circuit, &srs).unwrap();
+
+        for (x, y, z) in [(1, 2, 3), (1, 1, 7)] {
+            let circuit = parse(x, y, z);
+            // The structure of the circuit does not depend on the witness.
+            assert_eq!(circuit.compile().unwrap(), compiled_circuit);
+            let proof = generate_proof_with_key::<Sha256, _>(
+                &proving_key,
+                circuit.witness(),
+                &[],
+                KzgScheme::new(srs.clone()),
+                OpeningScheme::Kzg,
+            )
+            .unwrap();
+            assert!(
+                verify_with_key::<Sha256, _>(&verifying_key, proof, &[], OpeningScheme::Kzg)
+                    .is_ok()
+            );
+        }
+
+        // A witness that does not satisfy the equation is rejected by the prover, as its
+        // quotient is not a polynomial of the expected degree.
+        let error = generate_proof_with_key::<Sha256, _>(
+            &proving_key,
+            parse(1, 1, 1).witness(),
+            &[],
+            KzgScheme::new(srs),
+            OpeningScheme::Kzg,
+        )
+        .unwrap_err();
+        assert!(error.contains("exceeds"), "unexpected error: {error}");
+    }
+
+    #[test]
+    fn verifier_public_inputs_test() {
+        // One verifying key of x^3 + x + 5 = y serves every public value of y.
+        let parse = |x: u64, y: u64| {
+            let mut parser = Parser::default();
+            parser.add_witness("x", Fr::from(x));
+            parser.add_witness("y", Fr::from(y));
+            parser.add_public_input("y");
+            parser.parse("x^3+x+5=y")
+        };
+        let mut structure = Parser::<Fr>::default();
+        structure.add_public_input("y");
+        let compiled_circuit = structure.parse_structure("x^3+x+5=y").compile().unwrap();
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+        let (proving_key, verifying_key) = setup(&compiled_circuit, &srs).unwrap();
+
+        for (x, y) in [(3, 35), (2, 15)] {
+            let circuit = parse(x, y);
+            assert_eq!(circuit.compile().unwrap(), compiled_circuit);
+            assert_eq!(circuit.public_inputs(), vec![Fr::from(y)]);
+            let prove = || {
+                generate_proof_with_key::<Sha256, _>(
+                    &proving_key,
+                    circuit.witness(),
+                    &circuit.public_inputs(),
+                    KzgScheme::new(srs.clone()),
+                    OpeningScheme::Kzg,
+                )
+                .unwrap()
+            };
+            let public_inputs = [Fr::from(y)];
+            assert!(verify_with_key::<Sha256, _>(
+                &verifying_key,
+                prove(),
+                &public_inputs,
+                OpeningScheme::Kzg
+            )
+            .is_ok());
+            let other_inputs = [Fr::from(y + 1)];
+            assert!(verify_with_key::<Sha256, _>(
+                &verifying_key,
+                prove(),
+                &other_inputs,
+                OpeningScheme::Kzg
+            )
+            .is_err());
+            assert!(
+                verify_with_key::<Sha256, _>(&verifying_key, prove(), &[], OpeningScheme::Kzg)
+                    .is_err()
+            );
+        }
+
+        // A public value the witness does not satisfy is rejected by the prover, as the
+        // quotient is not a polynomial of the expected degree.
+        let error = generate_proof_with_key::<Sha256, _>(
+            &proving_key,
+            parse(3, 35).witness(),
+            &[Fr::from(36)],
+            KzgScheme::new(srs),
+            OpeningScheme::Kzg,
+        )
+        .unwrap_err();
+        assert!(error.contains("exceeds"), "unexpected error: {error}");
+    }
+
+    #[test]
+    fn verifier_rejects_tampered_srs() {
+        // check xyz = 6
+        let mut circuit = Circuit::default();
+        circuit.add_multiplication_gate(
+            (0, 0, Fr::from(1)),
+            (1, 0, Fr::from(2)),
+            (0, 1, Fr::from(2)),
+            Fr::from(0),
+        );
+        circuit.add_multiplication_gate(
+            (2, 0, Fr::from(2)),
+            (1, 1, Fr::from(3)),
+            (2, 1, Fr::from(6)),
+            Fr::from(0),
+        );
+        let compiled_circuit = circuit.compile().unwrap();
+
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+        let proof =
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs.clone())
+                .unwrap();
+
+        // Replace the third G1 point with the one of another SRS.
+        let mut srs_bytes = Vec::new();
+        srs.serialize_uncompressed(&mut srs_bytes).unwrap();
+        let mut other_bytes = Vec::new();
+        Srs::<Bls12_381>::new(compiled_circuit.size)
+            .serialize_uncompressed(&mut other_bytes)
+            .unwrap();
+        let point = 8 + 2 * 96..8 + 3 * 96;
+        srs_bytes[point.clone()].copy_from_slice(&other_bytes[point]);
+        let tampered =
+            Srs::<Bls12_381>::deserialize_uncompressed_unchecked(&srs_bytes[..]).unwrap();
+
+        assert!(verify::<Sha256, _>(&compiled_circuit, tampered, proof, &[]).is_err());
+    }
+
+    /// Generates a proof of `x^3 + x + 5 = y` with `y` public, and its verifying key.
+    fn public_input_proof(opening: OpeningScheme) -> (VerifyingKey, Proof, Fr) {
+        let mut parser = Parser::default();
+        parser.add_witness("x", Fr::from(3));
+        parser.add_witness("y", Fr::from(35));
+        parser.add_public_input("y");
+        let circuit = parser.parse("x^3+x+5=y");
+        let compiled_circuit = circuit.compile().unwrap();
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size + 6);
+        let (proving_key, verifying_key) = setup(&compiled_circuit, &srs).unwrap();
+        let proof = generate_proof_with_key::<Sha256, _>(
+            &proving_key,
+            circuit.witness(),
+            &circuit.public_inputs(),
+            KzgScheme::new(srs),
+            opening,
+        )
+        .unwrap();
+        (verifying_key, proof, circuit.public_inputs()[0])
+    }
+
+    #[test]
+    fn verifier_rejects_tampered_proof() {
+        type Tampering = (&'static str, fn(&mut Proof));
+        fn shift(commitment: &mut KzgCommitment) {
+            *commitment = commitment.clone() + KzgCommitment(G1Affine::generator());
+        }
+        let tamperings: [Tampering; 15] = [
+            ("a", |proof| shift(&mut proof.a_commit)),
+            ("b", |proof| shift(&mut proof.b_commit)),
+            ("c", |proof| shift(&mut proof.c_commit)),
+            ("z", |proof| shift(&mut proof.z_commit)),
+            ("t_lo", |proof| shift(&mut proof.t_lo_commit)),
+            ("t_mid", |proof| shift(&mut proof.t_mid_commit)),
+            ("t_hi", |proof| shift(&mut proof.t_hi_commit)),
+            ("w_ev_x", |proof| shift(&mut proof.w_ev_x_commit)),
+            ("w_ev_wx", |proof| shift(&mut proof.w_ev_wx_commit)),
+            ("bar_a", |proof| proof.bar_a += Fr::one()),
+            ("bar_b", |proof| proof.bar_b += Fr::one()),
+            ("bar_c", |proof| proof.bar_c += Fr::one()),
+            ("bar_s_sigma_1", |proof| proof.bar_s_sigma_1 += Fr::one()),
+            ("bar_s_sigma_2", |proof| proof.bar_s_sigma_2 += Fr::one()),
+            ("bar_z_w", |proof| proof.bar_z_w += Fr::one()),
+        ];
+
+        for opening in [OpeningScheme::Kzg, OpeningScheme::Shplonk] {
+            let (verifying_key, proof, y) = public_input_proof(opening);
+            let verify = |proof, y| {
+                verify_with_key::<Sha256, _>(&verifying_key, proof, &[y], opening).is_ok()
+            };
+            assert!(verify(proof.clone(), y));
+
+            for (component, tamper) in tamperings {
+                let mut tampered = proof.clone();
+                tamper(&mut tampered);
+                assert!(
+                    !verify(tampered, y),
+                    "{opening:?} proof with a tampered {component} should be rejected"
+                );
+            }
+            // Swapping the slices of the quotient polynomial moves their weights.
+            let mut swapped = proof.clone();
+            std::mem::swap(&mut swapped.t_lo_commit, &mut swapped.t_hi_commit);
+            assert!(!verify(swapped, y));
+            // Commitments to zero are not accepted in place of the wires.
+            let mut zeroed = proof.clone();
+            zeroed.a_commit = KzgCommitment(G1Affine::zero());
+            assert!(!verify(zeroed, y));
+            assert!(!verify(proof, y + Fr::one()));
+        }
+    }
+
+    #[test]
+    fn verifier_rejects_invalid_points() {
+        let (verifying_key, proof, y) = public_input_proof(OpeningScheme::Kzg);
+
+        // A point on the curve, outside the subgroup G1.
+        let mut x = Fq::one();
+        let outside = loop {
+            if let Some(point) = G1Affine::get_point_from_x_unchecked(x, false) {
+                if !point.is_in_correct_subgroup_assuming_on_curve() {
+                    break point;
+                }
+            }
+            x += Fq::one();
+        };
+        // A point off the curve.
+        let off_curve = G1Affine::new_unchecked(Fq::one(), Fq::one());
+
+        for point in [outside, off_curve] {
+            let mut invalid = proof.clone();
+            invalid.w_ev_x_commit = KzgCommitment(point);
+            let mut bytes = Vec::new();
+            invalid.serialize_uncompressed(&mut bytes).unwrap();
+            assert!(Proof::<Bls12_381>::deserialize_uncompressed(&bytes[..]).is_err());
+
+            let error =
+                verify_with_key::<Sha256, _>(&verifying_key, invalid, &[y], OpeningScheme::Kzg)
+                    .unwrap_err();
+            assert!(error.contains("Invalid proof"), "{error}");
+        }
+
+        // A scalar larger than the modulus is not a canonical encoding.
+        let mut bytes = Vec::new();
+        proof.serialize_uncompressed(&mut bytes).unwrap();
+        let bar_a = 9 * G1Affine::generator().uncompressed_size();
+        bytes[bar_a..bar_a + 32].fill(0xff);
+        assert!(Proof::<Bls12_381>::deserialize_uncompressed(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn prover_rejects_small_srs() {
+        // check xyz = 6
+        let mut circuit = Circuit::default();
+        circuit.add_multiplication_gate(
+            (0, 0, Fr::from(1)),
+            (1, 0, Fr::from(2)),
+            (0, 1, Fr::from(2)),
+            Fr::from(0),
+        );
+        circuit.add_multiplication_gate(
+            (2, 0, Fr::from(2)),
+            (1, 1, Fr::from(3)),
+            (2, 1, Fr::from(6)),
+            Fr::from(0),
+        );
+        let compiled_circuit = circuit.compile().unwrap();
+
+        let srs = Srs::<Bls12_381>::new(1);
+        let error =
+            generate_proof::<Sha256, _>(&compiled_circuit, circuit.witness(), &[], srs).err();
+        assert!(error.unwrap().contains("exceeds the maximum degree"));
+    }
+
+    #[test]
+    fn verifier_mapped_srs_test() {
+        // check xyz = 6
+        let mut circuit = Circuit::default();
+        circuit.add_multiplication_gate(
+            (0, 0, Fr::from(1)),
+            (1, 0, Fr::from(2)),
+            (0, 1, Fr::from(2)),
+            Fr::from(0),
+        );
+        circuit.add_multiplication_gate(
+            (2, 0, Fr::from(2)),
+            (1, 1, Fr::from(3)),
+            (2, 1, Fr::from(6)),
+            Fr::from(0),
+        );
+        let compiled_circuit = circuit.compile().unwrap();
+
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size);
+        let path = std::env::temp_dir().join(format!("plonk-srs-{}", std::process::id()));
+        std::fs::write(&path, srs.to_bytes()).unwrap();
+        let mapped = MappedSrs::<Bls12_381>::open(&path).unwrap();
+        let scheme = KzgScheme::new_mapped(mapped).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let proof = generate_proof_with_scheme::<Sha256, _>(
+            &compiled_circuit,
+            circuit.witness(),
+            &[],
+            scheme,
+            OpeningScheme::Kzg,
+        )
+        .unwrap();
+        assert!(verify::<Sha256, _>(&compiled_circuit, srs, proof, &[]).is_ok());
+    }
+
+    #[test]
+    fn verifier_transcript_hash_test() {
+        // check xyz = 6
+        let mut parser = Parser::default();
+        parser.add_witness("x", Fr::from(1));
+        parser.add_witness("y", Fr::from(2));
+        parser.add_witness("z", Fr::from(3));
+        let circuit = parser.parse("x*y*z=6");
+        let compiled_circuit = circuit.compile().unwrap();
+        let srs = Srs::<Bls12_381>::new(compiled_circuit.size + 3);
+
+        let prove = |hash| {
+            let prove = match hash {
+                TranscriptHash::Sha256 => generate_proof::<Sha256, _>,
+                TranscriptHash::Blake2b => generate_proof::<CkbBlake2b, _>,
+                TranscriptHash::Poseidon => generate_proof::<Poseidon, _>,
+            };
+            prove(&compiled_circuit, circuit.witness(), &[], srs.clone()).unwrap()
+        };
+        let verify = |hash, proof| {
+            let verify = match hash {
+                TranscriptHash::Sha256 => verify::<Sha256, _>,
+                TranscriptHash::Blake2b => verify::<CkbBlake2b, _>,
+                TranscriptHash::Poseidon => verify::<Poseidon, _>,
+            };
+            verify(&compiled_circuit, srs.clone(), proof, &[]).is_ok()
+        };
+
+        let hashes = [
+            TranscriptHash::Sha256,
+            TranscriptHash::Blake2b,
+            TranscriptHash::Poseidon,
+        ];
+        for prover_hash in hashes {
+            for verifier_hash in hashes {
+                assert_eq!(
+                    verify(verifier_hash, prove(prover_hash)),
+                    prover_hash == verifier_hash,
+                    "proved with {prover_hash}, verified with {verifier_hash}"
+                );
+            }
+        }
+    }
+
+    /// Proves and verifies `xyz = 6` on the given curve, with both opening schemes.
+    fn prove_on<E: Pairing>() {
+        let mut circuit = Circuit::<E::ScalarField>::default();
+        circuit.add_multiplication_gate(
+            (0, 0, E::ScalarField::from(1u64)),
+            (1, 0, E::ScalarField::from(2u64)),
+            (0, 1, E::ScalarField::from(2u64)),
+            E::ScalarField::zero(),
+        );
+        circuit.add_multiplication_gate(
+            (2, 0, E::ScalarField::from(2u64)),
+            (1, 1, E::ScalarField::from(3u64)),
+            (2, 1, E::ScalarField::from(6u64)),
+            E::ScalarField::zero(),
+        );
+        let compiled_circuit = circuit.compile().unwrap();
+
+        let srs = Srs::<E>::new(compiled_circuit.size);
+        for opening in [OpeningScheme::Kzg, OpeningScheme::Shplonk] {
+            let proof = generate_proof_with_opening::<Sha256, E>(
+                &compiled_circuit,
+                circuit.witness(),
+                &[],
+                srs.clone(),
+                opening,
+            )
+            .unwrap();
+            assert!(verify_with_opening::<Sha256, E>(
+                &compiled_circuit,
+                srs.clone(),
+                proof,
+                &[],
+                opening
+            )
+            .is_ok());
+        }
+    }
+
+    #[test]
+    fn verifier_other_curves_test() {
+        prove_on::<ark_bn254::Bn254>();
+        prove_on::<ark_bls12_377::Bls12_377>();
     }
 }
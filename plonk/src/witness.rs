@@ -0,0 +1,78 @@
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// Values of the wires of a circuit, assigned for each proof.
+///
+/// The values of gate `i` are the i-th values of the A, B and C wires. The dummy gates
+/// padding a compiled circuit have no values, their wires are zeros.
+#[derive(Clone, Debug, Default, PartialEq, CanonicalDeserialize, CanonicalSerialize)]
+pub struct Witness<F: PrimeField = Fr> {
+    /// Values of the A wire.
+    a: Vec<F>,
+    /// Values of the B wire.
+    b: Vec<F>,
+    /// Values of the C wire.
+    c: Vec<F>,
+}
+
+impl<F: PrimeField> Witness<F> {
+    /// Creates a witness from the values of the A, B and C wires.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the wires do not have the same number of values.
+    pub fn new(a: Vec<F>, b: Vec<F>, c: Vec<F>) -> Self {
+        assert!(
+            a.len() == b.len() && b.len() == c.len(),
+            "The wires must have the same number of values"
+        );
+        Self { a, b, c }
+    }
+
+    /// Appends the values of the wires of the next gate.
+    pub fn push(&mut self, a: F, b: F, c: F) {
+        self.a.push(a);
+        self.b.push(b);
+        self.c.push(c);
+    }
+
+    /// Returns the number of gates with values.
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    /// Returns `true` if no gate has values.
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    // Accessor methods for the values of each wire
+    pub fn a(&self) -> &[F] {
+        &self.a
+    }
+    pub fn b(&self) -> &[F] {
+        &self.b
+    }
+    pub fn c(&self) -> &[F] {
+        &self.c
+    }
+
+    /// Returns the values of the wires over an evaluation domain of the given size.
+    ///
+    /// Fails if the witness has more gates than the domain.
+    pub(crate) fn evaluations(&self, size: usize) -> Result<[Vec<F>; 3], String> {
+        if self.len() > size {
+            return Err(format!(
+                "Witness: {} gates do not fit in a circuit of size {}",
+                self.len(),
+                size
+            ));
+        }
+        Ok([&self.a, &self.b, &self.c].map(|values| {
+            let mut evals = values.clone();
+            evals.resize(size, F::zero());
+            evals
+        }))
+    }
+}